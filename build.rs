@@ -1,16 +1,24 @@
+use helpers::split_flags;
+use regex::Regex;
 use std::{
-    collections::HashSet,
+    collections::hash_map::DefaultHasher,
     fs,
+    hash::{Hash, Hasher},
+    io::ErrorKind,
     path::{Path, PathBuf},
-    process::Command,
+    process::{Command, Stdio},
+    time::SystemTime,
 };
 
+#[path = "build/helpers.rs"]
+mod helpers;
+
 #[derive(Debug)]
-struct IgnoreMacros(HashSet<String>);
+struct IgnoreMacros(Regex);
 
 impl bindgen::callbacks::ParseCallbacks for IgnoreMacros {
     fn will_parse_macro(&self, name: &str) -> bindgen::callbacks::MacroParsingBehavior {
-        if self.0.contains(name) {
+        if self.0.is_match(name) {
             bindgen::callbacks::MacroParsingBehavior::Ignore
         } else {
             bindgen::callbacks::MacroParsingBehavior::Default
@@ -25,124 +33,1695 @@ fn env(k: &str) -> Option<String> {
     }
 }
 
-fn compile(blis_build: &Path, out_dir: &Path) {
-    let mut configure = Command::new(blis_build.join("configure"));
-    configure
-        .current_dir(&blis_build)
-        .arg(format!("--prefix={}", out_dir.to_string_lossy()));
-    let threading = match (
-        env("CARGO_FEATURE_PARALLEL_PTHREADS"),
-        env("CARGO_FEATURE_PARALLEL_OPENMP"),
-    ) {
-        (Some(_), None) => "pthreads",
-        (None, Some(_)) => "openmp",
-        (None, None) => "no",
-        _ => panic!("Features 'parallel-pthreads' and 'parallel-openmp' are mutually exclusive."),
-    };
-    configure.arg(format!("--enable-threading={}", threading));
-    if env("CARGO_FEATURE_STATIC").is_some() {
-        configure.args(&["--enable-static", "--disable-shared"]);
+fn feature_enabled(feature: &str) -> bool {
+    env(&format!(
+        "CARGO_FEATURE_{}",
+        feature.to_uppercase().replace('-', "_")
+    ))
+    .is_some()
+}
+
+/// Looks up a compiler setting such as `CC` or `CFLAGS` for the target: `TARGET_CC` first, then
+/// the `cc` crate conventions, `CC_<triple>` and plain `CC`.
+fn tool_env(var: &str) -> Option<String> {
+    let target = env("TARGET").unwrap();
+    let names = [
+        format!("TARGET_{}", var),
+        format!("{}_{}", var, target),
+        format!("{}_{}", var, target.replace('-', "_")),
+        var.to_string(),
+    ];
+    for name in &names {
+        println!("cargo:rerun-if-env-changed={}", name);
+    }
+    names.iter().find_map(|name| env(name))
+}
+
+/// Returns `path` as BLIS's scripts and makefiles expect it, with forward slashes on Windows.
+fn shell_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    if env("HOST").unwrap().contains("windows") {
+        path.replace('\\', "/")
     } else {
-        configure.args(&["--disable-static", "--enable-shared"]);
+        path.into_owned()
+    }
+}
+
+fn mingw() -> bool {
+    env("CARGO_CFG_TARGET_OS").as_deref() == Some("windows")
+        && env("CARGO_CFG_TARGET_ENV").as_deref() == Some("gnu")
+}
+
+/// Returns the C compiler to build BLIS with. Without any setting, this is the compiler the `cc`
+/// crate picks for the target, e.g. `aarch64-linux-gnu-gcc` when cross-compiling, so that BLIS
+/// is built like the other `-sys` crates of the workspace.
+fn c_compiler() -> Option<String> {
+    tool_env("CC").or_else(|| {
+        let compiler = cc::Build::new()
+            .cargo_metadata(false)
+            .try_get_compiler()
+            .ok()?;
+        Some(compiler.path().to_string_lossy().into_owned())
+    })
+}
+
+/// Returns whether to instrument BLIS with AddressSanitizer, checking that the compiler can.
+fn asan() -> bool {
+    if env("CARGO_FEATURE_ASAN").is_none() {
+        return false;
+    }
+    let mut build = cc::Build::new();
+    build.cargo_metadata(false).warnings(false);
+    if let Some(cc) = c_compiler() {
+        build.compiler(cc);
+    }
+    if !build
+        .is_flag_supported("-fsanitize=address")
+        .unwrap_or(false)
+    {
+        panic!("Feature 'asan' requires a C compiler supporting -fsanitize=address, such as GCC or clang.");
+    }
+    true
+}
+
+/// Cargo features pinning a BLIS sub-configuration, and the configuration they select.
+const CONFIG_FEATURES: &[(&str, &str)] = &[
+    ("config-skylakex", "skx"),
+    ("config-zen3", "zen3"),
+    ("config-haswell", "haswell"),
+    ("config-thunderx2", "thunderx2"),
+    ("config-generic", "generic"),
+];
+
+fn config_feature() -> Option<&'static str> {
+    let features: Vec<_> = CONFIG_FEATURES
+        .iter()
+        .map(|(feature, _)| *feature)
+        .collect();
+    let feature = exclusive_feature(&features, "")?;
+    CONFIG_FEATURES
+        .iter()
+        .find(|(name, _)| *name == feature)
+        .map(|(_, confname)| *confname)
+}
+
+/// Returns the enabled feature of a group of mutually exclusive features, if any. `hint` is
+/// appended to the panic message when several are enabled.
+fn exclusive_feature(group: &[&'static str], hint: &str) -> Option<&'static str> {
+    let enabled: Vec<_> = group
+        .iter()
+        .copied()
+        .filter(|feature| feature_enabled(feature))
+        .collect();
+    match *enabled {
+        [] => None,
+        [feature] => Some(feature),
+        _ => panic!(
+            "Features {} are mutually exclusive. Cargo enables every feature requested anywhere \
+             in the dependency graph, so two crates probably ask for different ones; \
+             `cargo tree -e features -i blis-sys` shows which. Enable only one of them, from the \
+             top-level Cargo.toml.{}",
+            enabled
+                .iter()
+                .map(|feature| format!("'{}'", feature))
+                .collect::<Vec<_>>()
+                .join(", "),
+            hint
+        ),
+    }
+}
+
+fn memkind_enabled() -> bool {
+    env("CARGO_FEATURE_MEMKIND").is_some() && env("CARGO_CFG_TARGET_OS").as_deref() == Some("linux")
+}
+
+fn verbose_build() -> bool {
+    env("CARGO_FEATURE_VERBOSE_BUILD").is_some() || env("BLIS_SYS_VERBOSE").as_deref() == Some("1")
+}
+
+/// Follows the optimization level of the Cargo profile unless `BLIS_SYS_PROFILE` is set.
+fn blis_profile() -> &'static str {
+    match env("BLIS_SYS_PROFILE").as_deref() {
+        Some("debug") => "debug",
+        Some("release") => "release",
+        Some(profile) => panic!(
+            "BLIS_SYS_PROFILE must be 'debug' or 'release', not '{}'.",
+            profile
+        ),
+        None if env("OPT_LEVEL").as_deref() == Some("0") => "debug",
+        None => "release",
+    }
+}
+
+/// Returns the threading model selected by the features, as named by `--enable-threading`.
+fn threading() -> &'static str {
+    match exclusive_feature(
+        &["parallel-pthreads", "parallel-openmp"],
+        " Enabling neither builds a single-threaded BLIS, the default.",
+    ) {
+        Some("parallel-pthreads") => "pthreads",
+        Some(_) => "openmp",
+        None => "no",
+    }
+}
+
+/// Returns the sub-configurations to build with runtime dispatch from `BLIS_SYS_CONFIG_LIST`,
+/// instead of a whole family such as `x86_64`.
+fn config_list() -> Option<Vec<String>> {
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_CONFIG_LIST");
+    let list = env("BLIS_SYS_CONFIG_LIST")?;
+    if env("CARGO_FEATURE_RUNTIME_DISPATCH").is_none() {
+        println!(
+            "cargo:warning=BLIS_SYS_CONFIG_LIST is ignored without feature 'runtime-dispatch'."
+        );
+        return None;
     }
-    for var in &["CC", "FC", "RANLIB", "AR", "CFLAGS", "LDFLAGS"] {
-        if let Some(value) = env(&format!("TARGET_{}", var)) {
-            configure.arg(format!("{}={}", var, value));
+    // `blis_confname` warns that 'runtime-dispatch' is ignored without a family to dispatch in.
+    dispatch_family(&env("CARGO_CFG_TARGET_ARCH").unwrap())?;
+    let config_dir = in_tree_source()
+        .unwrap_or_else(|| PathBuf::from("upstream"))
+        .join("config");
+    let registry = env("BLIS_SYS_CONFIG_REGISTRY").map(PathBuf::from);
+    let list: Vec<_> = list.split_whitespace().map(str::to_string).collect();
+    for name in &list {
+        let exists = config_dir.join(name).is_dir()
+            || registry
+                .as_ref()
+                .is_some_and(|registry| registry.join(name).is_dir());
+        if !exists {
+            panic!(
+                "BLIS_SYS_CONFIG_LIST: there is no sub-configuration `{}` in {}.",
+                name,
+                config_dir.to_string_lossy()
+            );
         }
     }
+    Some(list)
+}
+
+/// BLIS's families of sub-configurations, all of which include `generic`.
+const FAMILIES: &[&str] = &[
+    "x86_64",
+    "intel64",
+    "amd64",
+    "amd64_legacy",
+    "arm64",
+    "arm32",
+];
+
+/// Families of sub-configurations that BLIS picks between at run time, by target architecture.
+fn dispatch_family(rust_arch: &str) -> Option<&'static str> {
+    match rust_arch {
+        "x86_64" => Some("x86_64"),
+        "aarch64" => Some("arm64"),
+        _ => None,
+    }
+}
+
+/// Returns the sub-configuration or family to configure BLIS for. BLIS only detects the CPU at
+/// run time for its own families, such as `x86_64`, so building a chosen set of
+/// sub-configurations redefines the target's family instead of registering a new one; its
+/// members are returned along with it.
+fn blis_confname() -> (String, Option<Vec<String>>) {
     let rust_arch = env("CARGO_CFG_TARGET_ARCH").unwrap();
     let blis_confname = if let Some(a) = env("BLIS_CONFNAME") {
         a
+    } else if let Some(confname) = config_feature() {
+        confname.to_string()
+    } else if let Some(mut list) = config_list() {
+        // Every family includes `generic`, which BLIS falls back to on other CPUs.
+        if !list.iter().any(|name| name == "generic") {
+            list.push("generic".to_string());
+        }
+        let family = dispatch_family(&rust_arch).unwrap();
+        return (family.to_string(), Some(list));
     } else {
-        match &*rust_arch {
-            "x86_64" => {
-                if env("CARGO_FEATURE_RUNTIME_DISPATCH").is_some() {
-                    "x86_64" // Build all microkernels; run-time dispatch
-                } else {
-                    "auto"
-                }
-            }
+        if env("CARGO_FEATURE_RUNTIME_DISPATCH").is_some()
+            && !["x86_64", "aarch64"].contains(&&*rust_arch)
+        {
+            println!(
+                "cargo:warning=BLIS has no run-time arch detection on {}; feature 'runtime-dispatch' is ignored.",
+                rust_arch
+            );
+        }
+        let confname = helpers::default_confname(
+            &rust_arch,
+            &env("CARGO_CFG_TARGET_ENDIAN").unwrap_or_default(),
+            &env("CARGO_CFG_TARGET_FEATURE").unwrap_or_default(),
+            feature_enabled,
+        );
+        if confname == "x86_64" && env("CARGO_FEATURE_RUNTIME_DISPATCH").is_none() {
+            println!(
+                "cargo:warning=The target only guarantees the x86_64 baseline, so BLIS is built for \
+                 every x86_64 CPU and picks the kernels at run time. Enable 'runtime-dispatch' to \
+                 silence this, or select a sub-configuration with `-C target-cpu`, a 'config-*' \
+                 feature or BLIS_CONFNAME."
+            );
+        }
+        confname.to_string()
+    };
+    if env("CARGO_FEATURE_WITH_REFERENCE").is_none()
+        || blis_confname == "generic"
+        || FAMILIES.contains(&&*blis_confname)
+    {
+        return (blis_confname, None);
+    }
+    if blis_confname == "auto" {
+        panic!("Feature 'with-reference' requires an explicit sub-configuration, e.g. through 'runtime-dispatch', a 'config-*' feature or BLIS_CONFNAME.");
+    }
+    let Some(family) = dispatch_family(&rust_arch) else {
+        panic!(
+            "Feature 'with-reference' requires a family of sub-configurations on {}, which has no run-time arch detection. Set BLIS_CONFNAME to one that includes `generic`.",
+            rust_arch
+        );
+    };
+    // Build the portable reference kernels alongside the optimized ones.
+    (
+        family.to_string(),
+        Some(vec![blis_confname, "generic".to_string()]),
+    )
+}
 
-            // BLIS does not have run-time arch detection on ARM or PowerPC.
-            // We'll let BLIS configure determine the best match.
-            "arm" | "armv7" => "auto", // cortexa9/cortexa15
-            "aarch64" => "auto",       // cortexa57/thunderx2
-            "powerpc64" => "auto",     // bgq/power9/power10
-            _ => "generic",
+/// Returns the arguments to pass to `configure` other than the prefix, which end with the
+/// sub-configuration `blis_confname`.
+fn configure_options(blis_confname: &str) -> Vec<String> {
+    let mut options = Vec::new();
+    let threading = threading();
+    options.push(format!("--enable-threading={}", threading));
+    if env("CARGO_FEATURE_OPENMP_DEFAULT").is_some() && threading != "openmp" {
+        panic!("Feature 'openmp-default' requires 'parallel-openmp'.");
+    }
+    options.extend(helpers::feature_options(feature_enabled));
+    if let Some(version) = env("BLIS_SYS_FORCE_VERSION") {
+        options.push(format!("--force-version={}", version));
+    }
+    // Without either option, BLIS uses `libmemkind` whenever the build host has it, which would
+    // then be missing from the link line.
+    if memkind_enabled() {
+        options.push("--with-memkind".to_string());
+    } else {
+        options.push("--without-memkind".to_string());
+    }
+    if verbose_build() {
+        options.push("--enable-verbose-make".to_string());
+    }
+    if env("CARGO_FEATURE_DEBUG_BLIS").is_some() {
+        options.push("--enable-debug".to_string());
+    } else if blis_profile() == "debug" {
+        // Keep the optimizations so that debug builds of dependents remain usable.
+        options.push("--enable-debug=opt".to_string());
+    }
+    if let Some(cc) = c_compiler() {
+        options.push(format!("CC={}", cc));
+    }
+    for var in &["FC", "RANLIB", "AR"] {
+        if let Some(value) = tool_env(var) {
+            options.push(format!("{}={}", var, value));
+        }
+    }
+    let mut cflags = split_flags(&tool_env("CFLAGS").unwrap_or_default());
+    let mut ldflags = split_flags(&tool_env("LDFLAGS").unwrap_or_default());
+    // BLIS only compiles position-independent code for its shared library, but a static one may
+    // still end up in a shared object, such as a `cdylib`.
+    if env("CARGO_FEATURE_STATIC").is_some()
+        && env("CARGO_CFG_TARGET_OS").as_deref() != Some("windows")
+    {
+        cflags.push("-fPIC".to_string());
+    }
+    if env("CARGO_FEATURE_SIZE_OPTIMIZED").is_some() {
+        cflags.push("-Os".to_string());
+        // The objects of a static library built with `-flto` only hold the compiler's IR, which
+        // the linker Rust uses can not read without the compiler's plugin.
+        if env("CARGO_FEATURE_STATIC").is_none() {
+            cflags.push("-flto".to_string());
+            ldflags.push("-flto".to_string());
         }
-        .to_string()
+    }
+    if let Some(lib) = env("BLIS_SYS_LIBM") {
+        // Link the shared library against the replacement before `-lm` resolves the same
+        // functions from the system's.
+        ldflags.push(format!("-l{}", lib));
+    }
+    let asan = asan();
+    if asan {
+        cflags.push("-fsanitize=address".to_string());
+        ldflags.push("-fsanitize=address".to_string());
+    }
+    if asan || env("CARGO_FEATURE_KEEP_FRAME_POINTERS").is_some() {
+        // Lets profilers that walk frame pointers, and ASan's reports, unwind through the
+        // kernels.
+        cflags.push("-fno-omit-frame-pointer".to_string());
+    }
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_MARCH");
+    if let Some(march) = env("BLIS_SYS_MARCH") {
+        // The optimized configurations pick their own `-march`, which this would conflict with.
+        if blis_confname == "generic" || blis_confname == "auto" {
+            cflags.extend(split_flags(&march));
+        } else {
+            println!(
+                "cargo:warning=BLIS_SYS_MARCH is ignored for sub-configuration '{}'; it only applies to 'generic' and 'auto'.",
+                blis_confname
+            );
+        }
+    }
+    options.extend(helpers::flags_arg("CFLAGS", &cflags));
+    options.extend(helpers::flags_arg("LDFLAGS", &ldflags));
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_CONFIGURE_ARGS");
+    if let Some(args) = env("BLIS_SYS_CONFIGURE_ARGS") {
+        // After the crate's own options, so these win, but before the configuration name,
+        // which `configure` expects last.
+        options.extend(split_flags(&args));
+    }
+    options.push(blis_confname.to_string());
+    options
+}
+
+/// `source` is the BLIS source tree and `blis_build` the directory to configure and build in,
+/// which are the same unless building from the sources in place.
+fn configure_command(
+    source: &Path,
+    blis_build: &Path,
+    out_dir: &Path,
+    options: &[String],
+) -> Command {
+    let mut configure = if env("HOST").unwrap().contains("windows") {
+        // Windows can not run the script directly, so hand it to the MSYS2 shell, which
+        // expects forward slashes.
+        let mut sh = Command::new("sh");
+        sh.arg(shell_path(&source.join("configure")));
+        sh
+    } else {
+        Command::new(source.join("configure"))
     };
-    configure.arg(blis_confname);
-    run(&mut configure);
+    configure
+        .current_dir(blis_build)
+        .arg(format!("--prefix={}", shell_path(out_dir)))
+        .args(options);
+    configure
+}
+
+/// Configures and builds BLIS, or only rebuilds it with the existing configuration unless
+/// `reconfigure`.
+fn compile(blis_build: &Path, configure: &mut Command, reconfigure: bool) {
+    if reconfigure {
+        run(configure);
+    } else {
+        // `make` does not track the flags the objects were compiled with.
+        run(make_command(blis_build, configure).arg("clean"));
+    }
+    run(make_command(blis_build, configure).arg("install"));
+    if env("CARGO_FEATURE_BUILD_TESTSUITE").is_some() {
+        // Links BLIS's test driver, `test_libblis.x`, against the library just built.
+        run(make_command(blis_build, configure).arg("testsuite-bin"));
+    }
+}
+
+fn make_command(blis_build: &Path, configure: &Command) -> Command {
     let makeflags = env("CARGO_MAKEFLAGS").unwrap();
-    run(Command::new("make")
-        .arg("install")
-        .env("MAKEFLAGS", makeflags)
-        .current_dir(&blis_build));
+    let mut make = Command::new(make_program());
+    make.env("MAKEFLAGS", makeflags).current_dir(blis_build);
+    if verbose_build() {
+        make.env("V", "1");
+    }
+    // `CARGO_MAKEFLAGS` does not always carry the job count, so pass it explicitly.
+    if let Some(jobs) = env("BLIS_SYS_MAKE_JOBS").or_else(|| env("NUM_JOBS")) {
+        make.arg(format!("-j{}", jobs));
+    }
+    if thin_archive() {
+        // Reference the object files from the archive instead of copying them into it.
+        make.arg("ARFLAGS=crT");
+    }
+    if let Some(extra) = extra_cflags() {
+        // `config.mk` records the configure-time flags as `CFLAGS_PRESET`, so extend them there.
+        // `configure` keeps the last `CFLAGS`, which may come from `BLIS_SYS_CONFIGURE_ARGS`.
+        let preset = configure
+            .get_args()
+            .filter_map(|arg| arg.to_str()?.strip_prefix("CFLAGS=").map(str::to_string))
+            .last()
+            .unwrap_or_default();
+        make.arg(helpers::cflags_preset_arg(&preset, &extra));
+    }
+    make
 }
 
-fn main() {
-    let out_dir = PathBuf::from(env("OUT_DIR").unwrap());
-    let lib_dir = out_dir.join("lib");
-    let lib = lib_dir.join("libblis.a");
-    if !lib.exists() {
-        let target = env("TARGET").unwrap();
-        let build_dir = out_dir.join(format!("blis_{}", target.to_lowercase()));
-        if build_dir.exists() {
-            fs::remove_dir_all(&build_dir).unwrap();
-        }
-        // Check if upstream is a non-empty directory.
-        if std::fs::read_dir("upstream")
+/// Returns the flags `BLIS_SYS_EXTRA_CFLAGS` adds to the compilation of BLIS, but not to
+/// `configure`.
+fn extra_cflags() -> Option<String> {
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_EXTRA_CFLAGS");
+    env("BLIS_SYS_EXTRA_CFLAGS")
+}
+
+/// Copies the test driver and its default input files from the build to `testsuite_dir`, so that
+/// the driver can be run from there.
+fn install_testsuite(source: &Path, blis_build: &Path, testsuite_dir: &Path) {
+    fs::create_dir_all(testsuite_dir).unwrap();
+    fs::copy(
+        blis_build.join("test_libblis.x"),
+        testsuite_dir.join("test_libblis.x"),
+    )
+    .unwrap();
+    for input in &["input.general", "input.operations"] {
+        fs::copy(
+            source.join("testsuite").join(input),
+            testsuite_dir.join(input),
+        )
+        .unwrap();
+    }
+}
+
+/// Returns the GNU make to build BLIS with, unless overridden by `BLIS_SYS_MAKE` or `MAKE`. BSD
+/// systems ship BSD make as `make`, which can not build BLIS, so `gmake` is tried first there.
+fn make_program() -> String {
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_MAKE");
+    println!("cargo:rerun-if-env-changed=MAKE");
+    if let Some(make) = env("BLIS_SYS_MAKE").or_else(|| env("MAKE")) {
+        return make;
+    }
+    let host = env("HOST").unwrap();
+    let candidates = if host.contains("bsd") || host.contains("dragonfly") {
+        ["gmake", "make"]
+    } else if host.contains("windows-gnu") {
+        // MinGW names its GNU make after itself, while MSYS2's own may be missing.
+        ["mingw32-make", "make"]
+    } else {
+        ["make", "gmake"]
+    };
+    let gnu_make = candidates.into_iter().find(|make| {
+        Command::new(make)
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains("GNU Make"))
+    });
+    match gnu_make {
+        Some(make) => make.to_string(),
+        None => panic!(
+            "GNU make was not found as `{}` or `{}`. Install it, or set BLIS_SYS_MAKE to its path.",
+            candidates[0], candidates[1]
+        ),
+    }
+}
+
+fn thin_archive() -> bool {
+    if env("CARGO_FEATURE_THIN_ARCHIVE").is_none() {
+        return false;
+    }
+    if env("CARGO_FEATURE_STATIC").is_none() {
+        panic!("Feature 'thin-archive' requires 'static'.");
+    }
+    true
+}
+
+fn link_kind() -> &'static str {
+    if thin_archive() {
+        // rustc can not bundle a thin archive into the rlib, so leave it to the final link.
+        "static:-bundle"
+    } else if env("CARGO_FEATURE_STATIC").is_some() {
+        "static"
+    } else {
+        "dylib"
+    }
+}
+
+fn link_blis() {
+    if bundled_dylib() {
+        println!("cargo:rustc-link-lib=dylib=blis_bundled");
+        return;
+    }
+    println!("cargo:rustc-link-lib={}=blis", link_kind());
+    if env("CARGO_FEATURE_STATIC").is_none() {
+        // A shared libblis links its math library itself, but a replacement must also win
+        // over the system's for the rest of the binary.
+        if let Some(lib) = env("BLIS_SYS_LIBM") {
+            println!("cargo:rustc-link-lib=dylib={}", lib);
+        }
+        return;
+    }
+    for lib in static_deps() {
+        println!("cargo:rustc-link-lib=dylib={}", lib);
+    }
+}
+
+/// Returns the math library the kernels call into, `BLIS_SYS_LIBM` or else the system's `m`.
+/// The math library is part of libc on Apple platforms and Windows.
+fn libm() -> Option<String> {
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_LIBM");
+    if let Some(lib) = env("BLIS_SYS_LIBM") {
+        return Some(lib);
+    }
+    match &*env("CARGO_CFG_TARGET_OS").unwrap() {
+        "linux" | "android" | "freebsd" | "netbsd" | "openbsd" | "dragonfly" | "solaris"
+        | "illumos" => Some("m".to_string()),
+        _ => None,
+    }
+}
+
+/// Returns the libraries a static libblis depends on, since it does not carry them itself.
+fn static_deps() -> Vec<String> {
+    let mut libs = Vec::new();
+    libs.extend(libm());
+    let target_os = env("CARGO_CFG_TARGET_OS").unwrap();
+    let target_env = env("CARGO_CFG_TARGET_ENV").unwrap_or_default();
+    // glibc before 2.34 ships `dlopen` and friends in a separate library.
+    if target_os == "linux" && target_env == "gnu" {
+        libs.push("dl".to_string());
+    }
+    if env("CARGO_FEATURE_BLAS").is_some() {
+        libs.extend(fortran_libs());
+    }
+    libs
+}
+
+fn bundled_dylib() -> bool {
+    if env("CARGO_FEATURE_BUNDLED_DYLIB").is_none() {
+        return false;
+    }
+    if env("CARGO_FEATURE_STATIC").is_none() {
+        panic!("Feature 'bundled-dylib' requires 'static'.");
+    }
+    if env("CARGO_CFG_TARGET_OS").as_deref() == Some("windows") {
+        panic!("Feature 'bundled-dylib' is not supported on Windows.");
+    }
+    true
+}
+
+fn bundled_dylib_name() -> &'static str {
+    if env("CARGO_CFG_TARGET_OS").as_deref() == Some("macos") {
+        "libblis_bundled.dylib"
+    } else {
+        "libblis_bundled.so"
+    }
+}
+
+/// Relinks the static `archive` into a shared library in `lib_dir` that exports all of BLIS, so
+/// that a host and its plugins can share a single copy.
+fn bundle_dylib(archive: &Path, lib_dir: &Path) {
+    let cc = c_compiler().unwrap_or_else(|| "cc".to_string());
+    let mut link = Command::new(cc);
+    link.arg("-o").arg(lib_dir.join(bundled_dylib_name()));
+    if env("CARGO_CFG_TARGET_OS").as_deref() == Some("macos") {
+        link.args([
+            "-dynamiclib",
+            "-install_name",
+            "@rpath/libblis_bundled.dylib",
+        ])
+        .arg(format!("-Wl,-force_load,{}", archive.to_string_lossy()));
+    } else {
+        link.args(["-shared", "-Wl,--whole-archive"])
+            .arg(archive)
+            .arg("-Wl,--no-whole-archive");
+    }
+    let libs = [static_deps(), threading_libs()].concat();
+    link.args(libs.iter().map(|lib| format!("-l{}", lib)));
+    run(&mut link);
+}
+
+/// Returns the runtime libraries of the Fortran compiler BLIS was configured with.
+fn fortran_libs() -> Vec<String> {
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_FORTRAN_LIBS");
+    if let Some(libs) = env("BLIS_SYS_FORTRAN_LIBS") {
+        return libs.split_whitespace().map(str::to_string).collect();
+    }
+    let fc = tool_env("FC").unwrap_or_default();
+    let libs: &[&str] = if fc.contains("gfortran") {
+        &["gfortran", "quadmath"]
+    } else if fc.contains("ifort") || fc.contains("ifx") {
+        &["ifcore", "imf"]
+    } else if fc.contains("flang") {
+        &["FortranRuntime", "FortranDecimal"]
+    } else {
+        &[]
+    };
+    libs.iter().map(|lib| lib.to_string()).collect()
+}
+
+/// Returns `blis.h` under `BLIS_SYS_INCLUDE_DIR`, which the bindings are generated from instead
+/// of the headers of the library, wherever it comes from.
+fn include_dir_header() -> Option<PathBuf> {
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_INCLUDE_DIR");
+    let header = PathBuf::from(env("BLIS_SYS_INCLUDE_DIR")?).join("blis/blis.h");
+    if !header.exists() {
+        panic!(
+            "BLIS_SYS_INCLUDE_DIR must contain blis/blis.h, but {} does not exist.",
+            header.to_string_lossy()
+        );
+    }
+    Some(header)
+}
+
+fn probe_prebuilt() -> Option<PathBuf> {
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_LIB_DIR");
+    let lib_dir = env("BLIS_SYS_LIB_DIR")?;
+    let header = include_dir_header().unwrap_or_else(|| {
+        panic!("BLIS_SYS_LIB_DIR requires BLIS_SYS_INCLUDE_DIR, which must contain blis/blis.h.")
+    });
+    println!("cargo:rustc-link-search=native={}", lib_dir);
+    println!("cargo:lib={}", lib_dir);
+    let include_dir = header.ancestors().nth(2).unwrap();
+    println!("cargo:include={}", include_dir.to_string_lossy());
+    link_blis();
+    Some(header)
+}
+
+fn probe_system() -> Option<PathBuf> {
+    env("CARGO_FEATURE_SYSTEM")?;
+    let library = pkg_config::Config::new()
+        .statik(env("CARGO_FEATURE_STATIC").is_some())
+        .probe("blis")
+        .ok()?;
+    for include_dir in &library.include_paths {
+        println!("cargo:include={}", include_dir.to_string_lossy());
+    }
+    for lib_dir in &library.link_paths {
+        println!("cargo:lib={}", lib_dir.to_string_lossy());
+    }
+    let header = library
+        .include_paths
+        .iter()
+        .flat_map(|dir| [dir.join("blis.h"), dir.join("blis/blis.h")])
+        .find(|header| header.exists())
+        .unwrap_or_else(|| {
+            panic!(
+                "pkg-config found blis, but blis.h is not in any of {:?}.",
+                library.include_paths
+            )
+        });
+    Some(header)
+}
+
+fn is_installed(lib_dir: &Path) -> bool {
+    if bundled_dylib() && !lib_dir.join(bundled_dylib_name()).exists() {
+        return false;
+    }
+    if env("CARGO_FEATURE_STATIC").is_some() {
+        return lib_dir.join("libblis.a").exists();
+    }
+    if mingw() {
+        // The DLL is linked through its import library.
+        return lib_dir.join("libblis.dll.a").exists();
+    }
+    // Shared libraries may carry a version suffix, e.g. `libblis.so.4` or `libblis.4.dylib`.
+    fs::read_dir(lib_dir)
+        .map(|entries| {
+            entries.filter_map(Result::ok).any(|entry| {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                name.starts_with("libblis.") && (name.contains(".so") || name.ends_with(".dylib"))
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// Checks the BLIS sources against the SHA-256 hashes recorded in `upstream.sha256`, which
+/// `BLIS_SYS_UPDATE_SOURCE_MANIFEST=1` regenerates from `source`, and rejects files it does not
+/// list.
+#[cfg(feature = "verify-source")]
+fn verify_sources(source: &Path) {
+    use sha2::{Digest, Sha256};
+
+    let manifest = Path::new("upstream.sha256");
+    println!("cargo:rerun-if-changed={}", manifest.to_string_lossy());
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_UPDATE_SOURCE_MANIFEST");
+    let hash = |path: &str| {
+        fs::read(source.join(path))
             .ok()
-            .and_then(|mut d| d.next().filter(|de| de.is_ok()))
-            .is_none()
+            .map(|contents| format!("{:x}", Sha256::digest(contents)))
+    };
+    if env("BLIS_SYS_UPDATE_SOURCE_MANIFEST").is_some() {
+        let mut files = Vec::new();
+        helpers::source_files(source, Path::new(""), &mut files);
+        files.sort();
+        let mut lines = String::from(MANIFEST_HEADER);
+        for file in &files {
+            lines.push_str(&format!("{}  {}\n", hash(file).unwrap(), file));
+        }
+        fs::write(manifest, lines).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(manifest).unwrap();
+    let expected: Vec<_> = expected
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            line.split_once("  ")
+                .expect("Malformed line in upstream.sha256")
+        })
+        .collect();
+    if expected.is_empty() {
+        panic!(
+            "upstream.sha256 lists no files. Generate it from a trusted checkout with \
+             BLIS_SYS_UPDATE_SOURCE_MANIFEST=1."
+        );
+    }
+    let mismatches: Vec<_> = expected
+        .iter()
+        .filter(|(digest, file)| hash(file).as_deref() != Some(*digest))
+        .map(|(_, file)| *file)
+        .collect();
+    if !mismatches.is_empty() {
+        panic!(
+            "{} of the BLIS sources in {} do not match upstream.sha256:\n{}",
+            mismatches.len(),
+            source.to_string_lossy(),
+            mismatches[..mismatches.len().min(20)].join("\n")
+        );
+    }
+    // BLIS's makefiles compile every source file they find, so added files change the library
+    // just like modified ones.
+    let listed: Vec<_> = expected.iter().map(|(_, file)| *file).collect();
+    let unlisted = helpers::unlisted_files(source, &listed);
+    if !unlisted.is_empty() {
+        panic!(
+            "{} files in {} are not listed in upstream.sha256:\n{}",
+            unlisted.len(),
+            source.to_string_lossy(),
+            unlisted[..unlisted.len().min(20)].join("\n")
+        );
+    }
+}
+
+#[cfg(feature = "verify-source")]
+const MANIFEST_HEADER: &str = "\
+# SHA-256 hashes of the BLIS sources in `upstream`, checked by the `verify-source` feature.
+# Regenerate with BLIS_SYS_UPDATE_SOURCE_MANIFEST=1 after updating the submodule.
+";
+
+#[cfg(not(feature = "verify-source"))]
+fn verify_sources(_source: &Path) {}
+
+fn copy_dir(src: &Path, dst: &Path) {
+    fs::create_dir_all(dst).unwrap();
+    for entry in fs::read_dir(src).unwrap() {
+        let entry = entry.unwrap();
+        let (src, dst) = (entry.path(), dst.join(entry.file_name()));
+        let file_type = entry.file_type().unwrap();
+        if file_type.is_symlink() {
+            copy_symlink(&src, &dst);
+        } else if file_type.is_dir() {
+            copy_dir(&src, &dst);
+        } else {
+            fs::copy(&src, &dst).unwrap();
+        }
+    }
+}
+
+#[cfg(unix)]
+fn copy_symlink(src: &Path, dst: &Path) {
+    std::os::unix::fs::symlink(fs::read_link(src).unwrap(), dst).unwrap();
+}
+
+/// Marks `configure` and the helper scripts under `build/` executable. Packages published from
+/// Windows do not record the executable bits, so the copy unpacked from the registry lacks them.
+#[cfg(unix)]
+fn restore_permissions(blis_build: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut scripts = vec![blis_build.join("configure")];
+    let mut dirs = vec![blis_build.join("build")];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(dir).into_iter().flatten() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path
+                .extension()
+                .is_some_and(|ext| ext == "sh" || ext == "py")
+            {
+                scripts.push(path);
+            }
+        }
+    }
+    for script in scripts {
+        let mut permissions = fs::metadata(&script).unwrap().permissions();
+        permissions.set_mode(permissions.mode() | 0o111);
+        fs::set_permissions(&script, permissions).unwrap();
+    }
+}
+
+#[cfg(not(unix))]
+fn restore_permissions(_blis_build: &Path) {}
+
+#[cfg(not(unix))]
+fn copy_symlink(src: &Path, dst: &Path) {
+    // Creating symlinks usually requires elevated privileges on Windows, so copy the target.
+    if src.is_dir() {
+        copy_dir(src, dst);
+    } else {
+        fs::copy(src, dst).unwrap();
+    }
+}
+
+fn strip(lib_dir: &Path) {
+    let strip = tool_env("STRIP").unwrap_or_else(|| "strip".to_string());
+    // Static archives still need their symbol tables, so only drop debug information from them.
+    let flag = if env("CARGO_FEATURE_STATIC").is_some() {
+        "-S"
+    } else {
+        "-x"
+    };
+    for entry in fs::read_dir(lib_dir).unwrap() {
+        let entry = entry.unwrap();
+        let is_lib = entry.file_name().to_string_lossy().starts_with("libblis.");
+        if !is_lib || entry.file_type().unwrap().is_symlink() {
+            continue;
+        }
+        match Command::new(&strip).arg(flag).arg(entry.path()).status() {
+            Ok(status) if status.success() => {}
+            Ok(status) => println!(
+                "cargo:warning=`{}` failed on {} with {}",
+                strip,
+                entry.path().to_string_lossy(),
+                status
+            ),
+            Err(_) => {
+                println!(
+                    "cargo:warning=`{}` is not available; libblis is not stripped",
+                    strip
+                );
+                return;
+            }
+        }
+    }
+}
+
+/// Returns the error checking level to build BLIS with, if it is not BLIS's default of full
+/// checking.
+fn error_checking_level() -> Option<&'static str> {
+    match exclusive_feature(&["error-check-full", "error-check-none"], "") {
+        Some("error-check-none") => Some("BLIS_NO_ERROR_CHECKING"),
+        _ => None,
+    }
+}
+
+/// BLIS has no configure option for its default error checking level, so patch the
+/// initializer of the global setting.
+fn set_error_checking_level(blis_build: &Path, level: &str) {
+    let path = blis_build.join("frame/base/bli_error.c");
+    let source = fs::read_to_string(&path).unwrap();
+    let default = "bli_err_chk_level = BLIS_FULL_ERROR_CHECKING;";
+    if !source.contains(default) {
+        panic!(
+            "Can not set the error checking level: `{}` not found in {}.",
+            default,
+            path.to_string_lossy()
+        );
+    }
+    let patched = source.replace(default, &format!("bli_err_chk_level = {};", level));
+    fs::write(&path, patched).unwrap();
+}
+
+/// Returns the identity of the BLIS sources, the changes made to them before configuring and the
+/// settings passed to `make`, which must invalidate previous builds just like the configure
+/// arguments do.
+fn build_settings() -> Vec<String> {
+    let mut settings = Vec::new();
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_CONFIG_REGISTRY");
+    if let Some(registry) = env("BLIS_SYS_CONFIG_REGISTRY") {
+        println!("cargo:rerun-if-changed={}", registry);
+        let modified = newest_modification(Path::new(&registry));
+        settings.push(format!("config-registry={}@{:?}", registry, modified));
+    }
+    if let Some(level) = error_checking_level() {
+        settings.push(format!("error-checking={}", level));
+    }
+    if env("CARGO_FEATURE_CUSTOM_ALLOC").is_some() {
+        settings.push("allocator-hooks".to_string());
+    }
+    if thin_archive() {
+        settings.push("thin-archive".to_string());
+    }
+    if env("CARGO_FEATURE_BUILD_TESTSUITE").is_some() {
+        settings.push("testsuite".to_string());
+    }
+    let in_tree_source = in_tree_source();
+    if let Some(source) = &in_tree_source {
+        settings.push(format!("source={}", source.to_string_lossy()));
+    }
+    settings.extend(source_identity(
+        in_tree_source.as_deref().unwrap_or(Path::new("upstream")),
+    ));
+    settings
+}
+
+/// Identifies the BLIS sources in `source` by their version, their commit when they are a git
+/// checkout, and the manifest of their hashes, so that a library built from other sources, e.g.
+/// before updating the submodule, is not taken for up to date.
+fn source_identity(source: &Path) -> Vec<String> {
+    use sha2::{Digest, Sha256};
+
+    let mut identity = Vec::new();
+    if let Ok(version) = fs::read_to_string(source.join("version")) {
+        identity.push(format!("version={}", version.trim()));
+    }
+    // Only ask git about an actual checkout, which a submodule is, rather than a repository
+    // containing the sources.
+    if source.join(".git").exists() {
+        let commit = Command::new("git")
+            .arg("-C")
+            .arg(source)
+            .args(["rev-parse", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success());
+        if let Some(output) = commit {
+            let commit = String::from_utf8_lossy(&output.stdout);
+            identity.push(format!("commit={}", commit.trim()));
+        }
+    }
+    println!("cargo:rerun-if-changed=upstream.sha256");
+    if let Ok(manifest) = fs::read("upstream.sha256") {
+        identity.push(format!("manifest={:x}", Sha256::digest(manifest)));
+    }
+    identity
+}
+
+/// `options` and `settings` are the arguments to `configure` and the other settings of the
+/// build, which key the cache along with the target.
+fn cache_dir(target: &str, options: &[String], settings: &[String]) -> Option<PathBuf> {
+    use sha2::{Digest, Sha256};
+
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_CACHE_DIR");
+    let cache_dir = env("BLIS_SYS_CACHE_DIR")?;
+    // The options capture the enabled features and compiler settings, and the settings the
+    // sources and the changes to them. Unlike `DefaultHasher`, SHA-256 hashes them the same way in every build.
+    let mut hasher = Sha256::new();
+    for part in options.iter().chain(settings) {
+        hasher.update(part);
+        hasher.update("\n");
+    }
+    let hash = format!("{:x}", hasher.finalize());
+    Some(PathBuf::from(cache_dir).join(format!("{}-{}", target, &hash[..16])))
+}
+
+/// Takes an exclusive lock on a file next to `dir`, which is released when the returned file is
+/// closed, including by a crashed build.
+fn lock_dir(dir: &Path) -> fs::File {
+    fs::create_dir_all(dir.parent().unwrap()).unwrap();
+    let mut path = dir.as_os_str().to_owned();
+    path.push(".lock");
+    let lock = fs::File::create(path).unwrap();
+    lock.lock().unwrap();
+    lock
+}
+
+fn newest_modification(dir: &Path) -> SystemTime {
+    let mut newest = fs::metadata(dir).unwrap().modified().unwrap();
+    for entry in fs::read_dir(dir).unwrap() {
+        let entry = entry.unwrap();
+        let modified = if entry.file_type().unwrap().is_dir() {
+            newest_modification(&entry.path())
+        } else {
+            entry.metadata().unwrap().modified().unwrap()
+        };
+        newest = newest.max(modified);
+    }
+    newest
+}
+
+/// Splices out-of-tree sub-configurations into the BLIS source tree. A `config_registry` file
+/// at the root of `registry` is appended to BLIS's own registry, so that new configurations and
+/// families can be named by `BLIS_CONFNAME`.
+fn merge_config_registry(registry: &Path, blis_build: &Path) {
+    let config_dir = blis_build.join("config");
+    copy_dir(registry, &config_dir);
+    let entries = config_dir.join("config_registry");
+    if entries.exists() {
+        let mut config_registry = fs::read_to_string(blis_build.join("config_registry")).unwrap();
+        config_registry.push('\n');
+        config_registry.push_str(&fs::read_to_string(&entries).unwrap());
+        fs::write(blis_build.join("config_registry"), config_registry).unwrap();
+        fs::remove_file(entries).unwrap();
+    }
+}
+
+/// Redefines `family` in the BLIS registry as `members`, so that BLIS picks between those at run
+/// time. The registry keeps the last definition of a name.
+fn redefine_family(blis_build: &Path, family: &str, members: &[String]) {
+    let path = blis_build.join("config_registry");
+    let mut config_registry = fs::read_to_string(&path).unwrap();
+    config_registry.push_str(&format!("\n{}: {}\n", family, members.join(" ")));
+    fs::write(&path, config_registry).unwrap();
+}
+
+/// Routes BLIS's pool, internal and user allocations through function pointers that
+/// `bli_sys_set_allocator` can replace, defaulting to `malloc` and `free`. The hooks use the
+/// `bli_` prefix so the bindings pick them up.
+fn add_allocator_hooks(blis_build: &Path) {
+    // The defaults are defined in `bli_kernel_macro_defs.h`, which `blis.h` includes after
+    // `BLIS_EXPORT_BLIS` is defined.
+    let path = blis_build.join("frame/include/bli_kernel_macro_defs.h");
+    let mut source = fs::read_to_string(&path).unwrap();
+    let first_default = "#ifndef BLIS_MALLOC_POOL";
+    let Some(start) = source.find(first_default) else {
+        panic!(
+            "Can not add the allocator hooks: `{}` not found in {}.",
+            first_default,
+            path.to_string_lossy()
+        );
+    };
+    let mut hooks = String::from(
+        "BLIS_EXPORT_BLIS void* bli_sys_malloc( size_t size );\n\
+         BLIS_EXPORT_BLIS void bli_sys_free( void* p );\n\
+         BLIS_EXPORT_BLIS void bli_sys_set_allocator( void* (*malloc_fp)( size_t ), void (*free_fp)( void* ) );\n\n",
+    );
+    for kind in &["POOL", "INTL", "USER"] {
+        hooks.push_str(&format!("#define BLIS_MALLOC_{} bli_sys_malloc\n", kind));
+        hooks.push_str(&format!("#define BLIS_FREE_{} bli_sys_free\n", kind));
+    }
+    hooks.push('\n');
+    source.insert_str(start, &hooks);
+    fs::write(&path, source).unwrap();
+    fs::write(
+        blis_build.join("frame/base/bli_sys_alloc.c"),
+        include_str!("src/bli_sys_alloc.c"),
+    )
+    .unwrap();
+}
+
+fn space_free_dir(dir: &Path) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    dir.hash(&mut hasher);
+    let temp_dir = std::env::temp_dir().join(format!("blis-sys-{:016x}", hasher.finish()));
+    if temp_dir.to_string_lossy().contains(' ') {
+        panic!(
+            "BLIS can not be built in {} because its path contains spaces. \
+             Set BLIS_SYS_CACHE_DIR to a directory without spaces.",
+            dir.to_string_lossy()
+        );
+    }
+    temp_dir
+}
+
+/// The BLIS release the `upstream` submodule is expected to be at.
+const EXPECTED_BLIS_VERSION: &str = "0.9.0";
+
+/// Warns when `upstream` is at a different release than expected, which usually means the
+/// submodule was not updated after a version bump. Forks can set `BLIS_SYS_EXPECTED_VERSION`.
+fn check_upstream_version() {
+    println!("cargo:rerun-if-changed=upstream/version");
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_EXPECTED_VERSION");
+    let Ok(version) = fs::read_to_string("upstream/version") else {
+        return;
+    };
+    let expected = env("BLIS_SYS_EXPECTED_VERSION").unwrap_or_else(|| EXPECTED_BLIS_VERSION.into());
+    if version.trim() != expected {
+        println!(
+            "cargo:warning=upstream is at BLIS {}, but {} was expected. Consider running \
+             `git submodule update`.",
+            version.trim(),
+            expected
+        );
+    }
+}
+
+/// An uninitialized submodule is an empty directory, and an interrupted checkout can miss files,
+/// so look for files configure needs rather than just a non-empty directory.
+fn check_sources(source: &Path) {
+    if source.join("configure").is_file() && source.join("frame").is_dir() {
+        return;
+    }
+    // Cargo writes `Cargo.toml.orig` into packages, which have no submodules to update.
+    let hint = if source != Path::new("upstream") {
+        "Point BLIS_SYS_IN_TREE_BUILD at a BLIS checkout."
+    } else if Path::new("Cargo.toml.orig").exists() {
+        "This package of blis-sys was published without them. Enable feature 'system' or set \
+         BLIS_SYS_LIB_DIR and BLIS_SYS_INCLUDE_DIR to use an installed BLIS instead, or depend \
+         on blis-sys through git."
+    } else {
+        "Consider running `git submodule update --init --recursive`."
+    };
+    panic!(
+        "{} does not contain the BLIS sources (`configure` and `frame/`). {}",
+        source.to_string_lossy(),
+        hint
+    );
+}
+
+/// Returns the BLIS source tree to build from without copying it, if `BLIS_SYS_IN_TREE_BUILD` is
+/// set: `upstream` for `1`, or the given directory. BLIS can be configured from another
+/// directory, so the build files still go to the build directory and the sources stay clean.
+fn in_tree_source() -> Option<PathBuf> {
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_IN_TREE_BUILD");
+    let source = env("BLIS_SYS_IN_TREE_BUILD")?;
+    let source = if source == "1" {
+        "upstream".to_string()
+    } else {
+        source
+    };
+    let source = fs::canonicalize(&source)
+        .unwrap_or_else(|e| panic!("BLIS_SYS_IN_TREE_BUILD: can not read {}: {}", source, e));
+    if source.to_string_lossy().contains(' ') {
+        panic!(
+            "BLIS can not be built from {} because its path contains spaces.",
+            source.to_string_lossy()
+        );
+    }
+    Some(source)
+}
+
+/// Lists the settings that modify the BLIS sources, which building in place rules out, with
+/// whether this build uses them. `redefines_family` tells whether a family of sub-configurations
+/// is redefined.
+fn source_patches(redefines_family: bool) -> [(&'static str, bool); 5] {
+    [
+        (
+            "BLIS_SYS_CONFIG_REGISTRY",
+            env("BLIS_SYS_CONFIG_REGISTRY").is_some(),
+        ),
+        (
+            "BLIS_SYS_CONFIG_LIST",
+            redefines_family && env("BLIS_SYS_CONFIG_LIST").is_some(),
+        ),
+        (
+            "'with-reference'",
+            redefines_family && env("CARGO_FEATURE_WITH_REFERENCE").is_some(),
+        ),
+        ("'error-check-none'", error_checking_level().is_some()),
+        (
+            "'custom-alloc'",
+            env("CARGO_FEATURE_CUSTOM_ALLOC").is_some(),
+        ),
+    ]
+}
+
+/// Builds and installs the vendored BLIS, returning its header and the arguments it was
+/// configured with.
+fn build_vendored(out_dir: &Path) -> (PathBuf, Vec<String>) {
+    check_upstream_version();
+    let target = env("TARGET").unwrap();
+    let (confname, members) = blis_confname();
+    let options = configure_options(&confname);
+    let mut settings = build_settings();
+    if let Some(members) = &members {
+        settings.push(format!("family={}", members.join(" ")));
+    }
+    let cache_dir = cache_dir(&target, &options, &settings);
+    // Held until the library is copied out of the cache, so that concurrent builds of the same
+    // configuration wait for each other instead of building over each other.
+    let _lock = cache_dir.as_deref().map(lock_dir);
+    let mut install_dir = cache_dir.unwrap_or_else(|| out_dir.to_path_buf());
+    // BLIS's makefiles break on paths containing spaces, so build elsewhere and copy back.
+    if install_dir.to_string_lossy().contains(' ') {
+        install_dir = space_free_dir(&install_dir);
+    }
+    let lib_dir = install_dir.join("lib");
+    let build_dir = install_dir.join(format!("blis_{}", target.to_lowercase()));
+    let in_tree_source = in_tree_source();
+    let source = in_tree_source.clone().unwrap_or_else(|| build_dir.clone());
+    let mut configure = configure_command(&source, &build_dir, &install_dir, &options);
+    // Reconfigure whenever the configure invocation differs from the one that produced the
+    // installed library, e.g. after changing `BLIS_CONFNAME` or `TARGET_CFLAGS`, or the sources
+    // differ from the ones it was built from.
+    let stamp = install_dir.join("configure-args");
+    let args: Vec<_> = configure
+        .get_args()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let args = [args, settings].concat().join("\n");
+    let up_to_date = fs::read_to_string(&stamp).is_ok_and(|previous| previous == args);
+    let header = install_dir.join("include/blis/blis.h");
+    let testsuite = env("CARGO_FEATURE_BUILD_TESTSUITE").is_some();
+    let testsuite_dir = install_dir.join("testsuite");
+    let reconfigure = !up_to_date
+        || !is_installed(&lib_dir)
+        || !header.exists()
+        || testsuite && !testsuite_dir.join("test_libblis.x").exists();
+    // The flags only passed to `make` have a stamp of their own, so that changing them rebuilds
+    // BLIS without reconfiguring it.
+    let make_stamp = install_dir.join("make-args");
+    let make_args = extra_cflags().unwrap_or_default();
+    let rebuild =
+        reconfigure || !fs::read_to_string(&make_stamp).is_ok_and(|previous| previous == make_args);
+    if rebuild {
+        if make_stamp.exists() {
+            fs::remove_file(&make_stamp).unwrap();
+        }
+        if reconfigure {
+            // Start from scratch, so that files left by an interrupted build or another
+            // configuration are not mixed into this one. Without the stamp, an interruption from
+            // here on triggers another full rebuild.
+            if stamp.exists() {
+                fs::remove_file(&stamp).unwrap();
+            }
+            for dir in [
+                &build_dir,
+                &lib_dir,
+                &install_dir.join("include"),
+                &testsuite_dir,
+            ] {
+                if dir.exists() {
+                    fs::remove_dir_all(dir).unwrap();
+                }
+            }
+            if let Some(source) = &in_tree_source {
+                let patches = source_patches(members.is_some());
+                let enabled: Vec<_> = patches
+                    .iter()
+                    .filter(|(_, enabled)| *enabled)
+                    .map(|(name, _)| *name)
+                    .collect();
+                if !enabled.is_empty() {
+                    let names: Vec<_> = patches.iter().map(|(name, _)| *name).collect();
+                    panic!(
+                        "BLIS_SYS_IN_TREE_BUILD can not be combined with {}, which modify the BLIS \
+                         sources. This build uses {}.",
+                        names.join(", "),
+                        enabled.join(", ")
+                    );
+                }
+                check_sources(source);
+                verify_sources(source);
+                fs::create_dir_all(&build_dir).unwrap();
+            } else {
+                check_sources(Path::new("upstream"));
+                verify_sources(Path::new("upstream"));
+                copy_dir(Path::new("upstream"), &build_dir);
+                restore_permissions(&build_dir);
+                if let Some(registry) = env("BLIS_SYS_CONFIG_REGISTRY") {
+                    merge_config_registry(Path::new(&registry), &build_dir);
+                }
+                if let Some(members) = &members {
+                    redefine_family(&build_dir, &confname, members);
+                }
+                if let Some(level) = error_checking_level() {
+                    set_error_checking_level(&build_dir, level);
+                }
+                if env("CARGO_FEATURE_CUSTOM_ALLOC").is_some() {
+                    add_allocator_hooks(&build_dir);
+                }
+            }
+        }
+        compile(&build_dir, &mut configure, reconfigure);
+        if testsuite {
+            install_testsuite(&source, &build_dir, &testsuite_dir);
+        }
+        if mingw() {
+            install_dlls(&install_dir, &lib_dir);
+        }
+        if bundled_dylib() {
+            let archive_dir = if thin_archive() {
+                thin_archive_dir(&build_dir)
+            } else {
+                lib_dir.clone()
+            };
+            bundle_dylib(&archive_dir.join("libblis.a"), &lib_dir);
+        }
+        println!("cargo:rerun-if-env-changed=BLIS_SYS_NO_STRIP");
+        if env("CARGO_FEATURE_SIZE_OPTIMIZED").is_some()
+            && env("BLIS_SYS_NO_STRIP").is_none()
+            && !thin_archive()
         {
-            panic!("upstream directory can not be read. Consider running `git submodule update --init`.");
+            strip(&lib_dir);
         }
-        run(Command::new("cp").arg("-R").arg("upstream").arg(&build_dir));
-        compile(&build_dir, &out_dir);
+        fs::write(&stamp, args).unwrap();
+        fs::write(&make_stamp, make_args).unwrap();
     }
+    if install_dir != out_dir {
+        let dirs: &[&str] = if testsuite {
+            &["lib", "include", "testsuite"]
+        } else {
+            &["lib", "include"]
+        };
+        for dir in dirs {
+            let dst = out_dir.join(dir);
+            if dst.exists() {
+                fs::remove_dir_all(&dst).unwrap();
+            }
+            copy_dir(&install_dir.join(dir), &dst);
+        }
+    }
+    // A thin archive refers to the object files relative to where it was built, so link the
+    // one in the build directory rather than the installed copy.
+    let lib_dir = if thin_archive() && !bundled_dylib() {
+        thin_archive_dir(&build_dir)
+    } else {
+        out_dir.join("lib")
+    };
     println!(
         "cargo:rustc-link-search=native={}",
         lib_dir.to_string_lossy()
     );
+    println!("cargo:lib={}", lib_dir.to_string_lossy());
+    println!("cargo:root={}", out_dir.to_string_lossy());
     let include_dir = out_dir.join("include");
     println!("cargo:include={}", include_dir.to_string_lossy());
 
-    let kind = if env("CARGO_FEATURE_STATIC").is_some() {
-        "static"
+    if testsuite {
+        let driver = out_dir.join("testsuite/test_libblis.x");
+        println!(
+            "cargo:rustc-env=BLIS_SYS_TESTSUITE={}",
+            driver.to_string_lossy()
+        );
+        println!("cargo:testsuite={}", driver.to_string_lossy());
+    }
+
+    if asan() {
+        // Link the sanitizer runtime into this crate's tests and binaries.
+        println!("cargo:rustc-link-arg=-fsanitize=address");
+    }
+
+    link_blis();
+    (include_dir.join("blis/blis.h"), options)
+}
+
+/// Moves DLLs installed to `bin` next to their import libraries, since Cargo puts the link
+/// search paths on `PATH` for `cargo run` and `cargo test`, but not `bin`.
+fn install_dlls(install_dir: &Path, lib_dir: &Path) {
+    let bin_dir = install_dir.join("bin");
+    for entry in fs::read_dir(&bin_dir).into_iter().flatten() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|ext| ext == "dll") {
+            fs::rename(&path, lib_dir.join(path.file_name().unwrap())).unwrap();
+        }
+    }
+}
+
+/// Returns the directory BLIS built its library in, `lib/<configuration>` in the build directory.
+fn thin_archive_dir(blis_build: &Path) -> PathBuf {
+    fs::read_dir(blis_build.join("lib"))
+        .into_iter()
+        .flatten()
+        .map(|entry| entry.unwrap().path())
+        .find(|dir| dir.join("libblis.a").exists())
+        .unwrap_or_else(|| {
+            panic!(
+                "No libblis.a found under {}.",
+                blis_build.join("lib").to_string_lossy()
+            )
+        })
+}
+
+fn openmp_lib() -> String {
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_OMP_LIB");
+    if let Some(lib) = env("BLIS_SYS_OMP_LIB") {
+        return lib;
+    }
+    // GCC ships libgomp, while clang and Apple toolchains use LLVM's libomp. Ask the compiler
+    // that builds BLIS, which is also the one whose runtime its objects need.
+    let cc = c_compiler().unwrap_or_else(|| "cc".to_string());
+    let is_clang = env("CARGO_CFG_TARGET_VENDOR").as_deref() == Some("apple")
+        || Command::new(&cc)
+            .arg("--version")
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).contains("clang"))
+            .unwrap_or(false);
+    if is_clang { "omp" } else { "gomp" }.to_string()
+}
+
+/// Returns the libraries of the runtime BLIS's threads need.
+fn threading_libs() -> Vec<String> {
+    helpers::threading_libs(
+        threading(),
+        openmp_lib,
+        &env("CARGO_CFG_TARGET_OS").unwrap(),
+        &env("CARGO_CFG_TARGET_ENV").unwrap_or_default(),
+    )
+}
+
+fn clang_args() -> Vec<String> {
+    let cflags = split_flags(&tool_env("CFLAGS").unwrap_or_default());
+    helpers::clang_args(&env("HOST").unwrap(), &env("TARGET").unwrap(), &cflags)
+}
+
+fn blis_version(header: &Path) -> String {
+    // `blis.h` is flattened on install, so it carries the definition from `bli_config.h`.
+    let header = fs::read_to_string(header).unwrap_or_default();
+    header
+        .lines()
+        .find_map(|line| {
+            line.trim()
+                .strip_prefix("#define BLIS_VERSION_STRING")
+                .map(|version| version.trim().trim_matches('"').to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn main() {
+    let out_dir = PathBuf::from(env("OUT_DIR").unwrap());
+    let (source, header, configure_args) = if let Some(header) = probe_prebuilt() {
+        ("prebuilt", header, Vec::new())
+    } else if let Some(header) = probe_system() {
+        ("system", header, Vec::new())
     } else {
-        "dylib"
+        let (header, configure_args) = build_vendored(&out_dir);
+        ("vendored", header, configure_args)
     };
-    println!("cargo:rustc-link-lib={}=blis", kind);
+    let version = blis_version(&header);
+    // Generate the bindings from other headers than the library's, which should still be of the
+    // same version.
+    let header = match include_dir_header() {
+        Some(include_header) if source != "prebuilt" => {
+            let header_version = blis_version(&include_header);
+            if header_version != version {
+                println!(
+                    "cargo:warning=The headers in BLIS_SYS_INCLUDE_DIR are of BLIS {}, but the {} library is {}.",
+                    header_version, source, version
+                );
+            }
+            include_header
+        }
+        _ => header,
+    };
+    if env("CARGO_FEATURE_CUSTOM_ALLOC").is_some()
+        && !fs::read_to_string(&header).is_ok_and(|header| header.contains("bli_sys_set_allocator"))
+    {
+        panic!(
+            "Feature 'custom-alloc' requires the vendored BLIS, but {} has no allocator hooks.",
+            header.to_string_lossy()
+        );
+    }
+    if env("CARGO_FEATURE_BUILD_TESTSUITE").is_some() && source != "vendored" {
+        panic!(
+            "Feature 'build-testsuite' requires the vendored BLIS, but a {} BLIS was found.",
+            source
+        );
+    }
+    println!("cargo:rustc-env=BLIS_SYS_VERSION={}", version);
+    println!("cargo:version={}", version);
+    let build_info = out_dir.join("build-info.json");
+    let info = describe_build(source, &version, &configure_args);
+    fs::write(&build_info, build_info_json(&info)).unwrap();
+    if env("CARGO_FEATURE_PRINT_CONFIG").is_some() {
+        let summary: Vec<_> = info
+            .iter()
+            .filter(|(key, _)| {
+                ["source", "confname", "threading", "link", "int_size"].contains(key)
+            })
+            .map(|(key, value)| format!("{}={}", key, value.as_deref().unwrap_or("unknown")))
+            .collect();
+        println!("cargo:warning=BLIS {}: {}", version, summary.join(", "));
+    }
+    println!(
+        "cargo:rustc-env=BLIS_SYS_BUILD_INFO={}",
+        build_info.to_string_lossy()
+    );
+    println!("cargo:build_info={}", build_info.to_string_lossy());
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_DEFAULT_THREADS");
+    if let Some(threads) = env("BLIS_SYS_DEFAULT_THREADS") {
+        if !threads.parse::<usize>().is_ok_and(|n| n > 0) {
+            panic!(
+                "BLIS_SYS_DEFAULT_THREADS must be a positive number of threads, not `{}`.",
+                threads
+            );
+        }
+        if threading() == "no" {
+            println!("cargo:warning=BLIS_SYS_DEFAULT_THREADS has no effect without a 'parallel-*' feature.");
+        }
+        println!("cargo:rustc-env=BLIS_SYS_DEFAULT_THREADS={}", threads);
+    }
+    for directive in helpers::link_lib_directives(&threading_libs()) {
+        println!("{}", directive);
+    }
+    if memkind_enabled() {
+        println!("cargo:rustc-link-lib=dylib=memkind");
+    } else if env("CARGO_FEATURE_MEMKIND").is_some() {
+        println!("cargo:warning=Feature 'memkind' is only supported on Linux and has no effect.");
+    }
     println!("cargo:rerun-if-changed=build.rs");
+    for var in &[
+        "BLIS_CONFNAME",
+        "TARGET_CC",
+        "TARGET_FC",
+        "TARGET_RANLIB",
+        "TARGET_AR",
+        "TARGET_CFLAGS",
+        "TARGET_LDFLAGS",
+        "BLIS_SYS_MAKE_JOBS",
+        "BLIS_SYS_FORCE_VERSION",
+        "BLIS_SYS_VERBOSE",
+        "BLIS_SYS_PROFILE",
+        "BLIS_SYS_LIBM",
+        "CARGO_FEATURE_STATIC",
+        "CARGO_FEATURE_PARALLEL_PTHREADS",
+        "CARGO_FEATURE_PARALLEL_OPENMP",
+        "CARGO_FEATURE_RUNTIME_DISPATCH",
+    ] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+
+    let out_path = out_dir.join("bindings.rs");
+    generate_bindings(&header, &out_path);
+    if env("CARGO_CFG_TARGET_OS").as_deref() == Some("macos") && declares_blas(&out_path) {
+        let hint = if source == "vendored" {
+            "Disable features 'blas' and 'cblas'"
+        } else {
+            "Use a BLIS configured with `--disable-blas`"
+        };
+        println!(
+            "cargo:warning=The {} BLIS exports `dgemm_` and the other BLAS symbols, which clash \
+             with Apple's Accelerate framework if anything else links it. {} and use the \
+             `bli_*` functions in that case.",
+            source, hint
+        );
+    }
+}
 
+/// Returns whether the bindings declare the Fortran-style BLAS functions, which `blis.h` only
+/// does when the library was configured with its BLAS layer, however it was obtained.
+fn declares_blas(bindings: &Path) -> bool {
+    fs::read_to_string(bindings).is_ok_and(|bindings| bindings.contains("fn dgemm_"))
+}
+
+fn json_string(value: Option<&str>) -> String {
+    let Some(value) = value else {
+        return "null".to_string();
+    };
+    let mut json = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Describes how BLIS was obtained and configured, for `build-info.json` and `print-config`. The
+/// configure settings are only known for vendored builds, so `args` is empty otherwise.
+fn describe_build(
+    source: &str,
+    version: &str,
+    args: &[String],
+) -> Vec<(&'static str, Option<String>)> {
+    let setting = |name: &str| {
+        args.iter()
+            .find_map(|arg| arg.strip_prefix(name)?.strip_prefix('='))
+            .map(str::to_string)
+    };
+    let int_size = if env("CARGO_FEATURE_ILP64").is_some() {
+        "64"
+    } else {
+        "auto"
+    };
+    vec![
+        ("version", Some(version.to_string())),
+        ("source", Some(source.to_string())),
+        ("confname", args.last().cloned()),
+        ("threading", Some(threading().to_string())),
+        ("link", Some(link_kind().to_string())),
+        ("int_size", Some(int_size.to_string())),
+        ("cc", setting("CC")),
+        ("cflags", setting("CFLAGS")),
+        ("ldflags", setting("LDFLAGS")),
+    ]
+}
+
+fn build_info_json(info: &[(&str, Option<String>)]) -> String {
+    let fields: Vec<_> = info
+        .iter()
+        .map(|(key, value)| format!("  \"{}\": {}", key, json_string(value.as_deref())))
+        .collect();
+    format!("{{\n{}\n}}\n", fields.join(",\n"))
+}
+
+fn generate_bindings(header: &Path, out_path: &Path) {
+    // These macros conflict with constants of the same name in other system headers.
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_BLOCK_MACROS");
+    let mut ignored = vec!["FP_.*".to_string(), "IPPORT_RESERVED".to_string()];
+    ignored.extend(env("BLIS_SYS_BLOCK_MACROS"));
     let ignored_macros = IgnoreMacros(
-        vec![
-            "FP_INFINITE".into(),
-            "FP_NAN".into(),
-            "FP_NORMAL".into(),
-            "FP_SUBNORMAL".into(),
-            "FP_ZERO".into(),
-            "IPPORT_RESERVED".into(),
-        ]
-        .into_iter()
-        .collect(),
+        Regex::new(&format!("^(?:{})$", ignored.join("|")))
+            .expect("Invalid BLIS_SYS_BLOCK_MACROS pattern"),
     );
 
-    let bindings = bindgen::Builder::default()
-        .header(include_dir.join("blis/blis.h").to_string_lossy())
+    // Only generate BLIS's public surface rather than everything its headers pull in.
+    let mut builder = bindgen::Builder::default()
+        .header(header.to_string_lossy())
+        .clang_args(clang_args())
+        // Resolve includes from `blis.h`, such as `cblas.h`, next to it rather than from the
+        // host's default search path.
+        .clang_arg(format!("-I{}", header.parent().unwrap().to_string_lossy()))
         .parse_callbacks(Box::new(ignored_macros))
-        .generate()
-        .expect("Unable to generate bindings");
+        .allowlist_function("bli_.*")
+        .allowlist_function("cblas_.*")
+        .allowlist_function("[a-z0-9_]+_") // Fortran-style BLAS, e.g. `dgemm_`
+        .allowlist_type(".*_t")
+        .allowlist_type("[sd]complex")
+        .allowlist_var("BLIS_.*")
+        .allowlist_var("CBLAS_.*")
+        // Generate enums as newtypes with associated constants, e.g. `trans_t::BLIS_TRANSPOSE`,
+        // so that their values can not be mixed up with plain integers or other enums. Unlike
+        // Rust enums, they stay sound if a newer BLIS adds values.
+        .default_enum_style(bindgen::EnumVariation::NewType { is_bitfield: false })
+        // Packing states are combinations of flags.
+        .bitfield_enum("pack_t")
+        // bindgen skips the derives a type can not support, such as `PartialEq` on unions and
+        // on structs containing them.
+        .derive_debug(true)
+        .derive_default(true)
+        .derive_partialeq(true)
+        // An object points to its root, usually itself, so comparing objects field by field
+        // compares addresses rather than contents.
+        .no_partialeq("obj_[st]");
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_ALLOWLIST");
+    for pattern in env("BLIS_SYS_ALLOWLIST")
+        .unwrap_or_default()
+        .split_whitespace()
+    {
+        builder = builder
+            .allowlist_function(pattern)
+            .allowlist_type(pattern)
+            .allowlist_var(pattern);
+    }
+    println!("cargo:rerun-if-env-changed=BLIS_SYS_WRAPPER_HEADER");
+    if let Some(wrapper) = env("BLIS_SYS_WRAPPER_HEADER") {
+        // Parsed after `blis.h`, so the wrapper can use BLIS's types without including it.
+        println!("cargo:rerun-if-changed={}", wrapper);
+        builder = builder.header(wrapper);
+    }
+    let bindings = builder.generate().expect("Unable to generate bindings");
 
-    let out_path = PathBuf::from(std::env::var("OUT_DIR").unwrap());
     bindings
-        .write_to_file(out_path.join("bindings.rs"))
+        .write_to_file(out_path)
         .expect("Couldn't write bindings");
 }
 
+const TOOLCHAIN_HINT: &str =
+    "Ensure a C toolchain and `make` are installed; set TARGET_CC to override the C compiler.";
+
 fn run(command: &mut Command) {
     println!("Running: `{:?}`", command);
-    assert!(command.status().unwrap().success());
+    let program = command.get_program().to_string_lossy().into_owned();
+    let child = match command.stderr(Stdio::piped()).spawn() {
+        Ok(child) => child,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            panic!("`{}` was not found. {}", program, TOOLCHAIN_HINT)
+        }
+        Err(e) => panic!("Failed to run `{}`: {}", program, e),
+    };
+    let output = child.wait_with_output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    eprint!("{}", stderr);
+    if !output.status.success() {
+        let lines: Vec<_> = stderr.lines().collect();
+        panic!(
+            "`{}` failed with {}:\n{}\n\n{}",
+            program,
+            output.status,
+            lines[lines.len().saturating_sub(40)..].join("\n"),
+            TOOLCHAIN_HINT
+        );
+    }
 }
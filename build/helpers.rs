@@ -0,0 +1,679 @@
+//! Helpers of the build script that only depend on their arguments, so that
+//! `tests/build_helpers.rs` can test them.
+
+use std::fs;
+use std::path::Path;
+
+/// Splits compiler flags into words like a POSIX shell does, honoring quotes and backslashes, so
+/// that e.g. `-I"/path with space"` stays a single flag.
+pub fn split_flags(flags: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = None::<String>;
+    let mut chars = flags.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                word.extend(chars.by_ref().take_while(|&c| c != '\''));
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => word.extend(chars.next()),
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => word.get_or_insert_with(String::new).extend(chars.next()),
+            c if c.is_whitespace() => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+/// Joins flags into a value for BLIS's makefiles. They expand `CFLAGS_PRESET` and `LDFLAGS` into
+/// the shell commands of their recipes unquoted, so flags containing spaces or other special
+/// characters are quoted for that shell.
+pub fn shell_flags(flags: &[String]) -> String {
+    let quote = |flag: &String| {
+        let plain = !flag.is_empty()
+            && flag
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_=+/.,:@%".contains(c));
+        if plain {
+            flag.clone()
+        } else {
+            format!("'{}'", flag.replace('\'', "'\\''"))
+        }
+    };
+    flags.iter().map(quote).collect::<Vec<_>>().join(" ")
+}
+
+/// Returns the `name=value` argument passing `flags` to `configure`, unless there are none.
+pub fn flags_arg(name: &str, flags: &[String]) -> Option<String> {
+    if flags.is_empty() {
+        None
+    } else {
+        Some(format!("{}={}", name, shell_flags(flags)))
+    }
+}
+
+/// Returns the `make` argument appending the flags in `extra` to the `CFLAGS` that `configure`
+/// received, `preset`, which `config.mk` records as `CFLAGS_PRESET`.
+pub fn cflags_preset_arg(preset: &str, extra: &str) -> String {
+    let extra = shell_flags(&split_flags(extra));
+    format!("CFLAGS_PRESET={} {}", preset, extra)
+        .trim()
+        .to_string()
+}
+
+/// Returns the `configure` options that only depend on the Cargo features, which `enabled` tells
+/// apart by name.
+pub fn feature_options(enabled: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut options = Vec::new();
+    if enabled("aocl-dynamic") {
+        if !enabled("parallel-pthreads") && !enabled("parallel-openmp") {
+            panic!("Feature 'aocl-dynamic' requires 'parallel-pthreads' or 'parallel-openmp'.");
+        }
+        // Only AMD's fork of BLIS (AOCL-BLIS) understands this flag.
+        options.push("--enable-aocl-dynamic".to_string());
+    }
+    if enabled("static") {
+        options.extend(["--enable-static", "--disable-shared"].map(String::from));
+    } else {
+        options.extend(["--disable-static", "--enable-shared"].map(String::from));
+    }
+    if enabled("sup") {
+        options.push("--enable-sup-handling".to_string());
+    } else {
+        options.push("--disable-sup-handling".to_string());
+    }
+    if enabled("trsm-preinversion") {
+        options.push("--enable-trsm-preinversion".to_string());
+    } else {
+        options.push("--disable-trsm-preinversion".to_string());
+    }
+    match (enabled("omp-simd"), enabled("no-omp-simd")) {
+        (true, true) => panic!("Features 'omp-simd' and 'no-omp-simd' are mutually exclusive."),
+        (true, false) => options.push("--enable-pragma-omp-simd".to_string()),
+        (false, true) => options.push("--disable-pragma-omp-simd".to_string()),
+        // Leave it to BLIS's default.
+        (false, false) => {}
+    }
+    // BLIS supports mixed datatypes by default, including the optimizations using extra memory.
+    if enabled("mixed-dt-extra-mem") && !enabled("mixed-dt") {
+        panic!("Feature 'mixed-dt-extra-mem' requires 'mixed-dt'.");
+    }
+    if enabled("mixed-dt") {
+        options.push("--enable-mixed-dt".to_string());
+        if enabled("mixed-dt-extra-mem") {
+            options.push("--enable-mixed-dt-extra-mem".to_string());
+        } else {
+            options.push("--disable-mixed-dt-extra-mem".to_string());
+        }
+    } else {
+        options.push("--disable-mixed-dt".to_string());
+    }
+    if enabled("no-pools") {
+        // Allocate and free packing buffers and small blocks per operation instead of reusing them.
+        options.extend(["--disable-pba-pools", "--disable-sba-pools"].map(String::from));
+    }
+    if enabled("ilp64") {
+        options.extend(["--int-size=64", "--blas-int-size=64"].map(String::from));
+    }
+    // BLIS builds the BLAS layer by default, and CBLAS is built on top of it.
+    if enabled("blas") || enabled("cblas") {
+        options.push("--enable-blas".to_string());
+    } else {
+        options.push("--disable-blas".to_string());
+    }
+    if enabled("cblas") {
+        // `blis.h` includes `cblas.h` when enabled, so bindgen picks up the `cblas_*` symbols.
+        options.push("--enable-cblas".to_string());
+    }
+    if enabled("complex-return-intel") {
+        // Return complex values from the BLAS interface through a hidden pointer argument, like
+        // code compiled by the Intel Fortran compiler expects, instead of in registers.
+        options.push("--complex-return=intel".to_string());
+    }
+    options
+}
+
+/// Picks the most capable BLIS sub-configuration whose instructions the target is guaranteed to
+/// have. `auto` would detect the build host's CPU instead, whose kernels may crash on older
+/// machines. Targets without extensions beyond the `x86_64` baseline get the whole `x86_64`
+/// family, which checks the CPU at run time, since the portable kernels are far slower.
+/// `target_features` is `CARGO_CFG_TARGET_FEATURE`.
+pub fn x86_64_confname(target_features: &str) -> &'static str {
+    let has = |features: &[&str]| {
+        features
+            .iter()
+            .all(|feature| target_features.split(',').any(|f| f == *feature))
+    };
+    if has(&["avx512f", "avx512dq", "avx512bw", "avx512vl", "fma"]) {
+        "skx"
+    } else if has(&["avx2", "fma"]) {
+        "haswell"
+    } else if has(&["avx"]) {
+        "sandybridge"
+    } else {
+        "x86_64"
+    }
+}
+
+/// Returns the sub-configuration for `rust_arch` when neither `BLIS_CONFNAME` nor a feature
+/// selects one. `endian` and `target_features` are `CARGO_CFG_TARGET_ENDIAN` and
+/// `CARGO_CFG_TARGET_FEATURE`, and `enabled` tells the Cargo features apart by name.
+pub fn default_confname(
+    rust_arch: &str,
+    endian: &str,
+    target_features: &str,
+    enabled: impl Fn(&str) -> bool,
+) -> &'static str {
+    match rust_arch {
+        "x86_64" => {
+            if enabled("runtime-dispatch") {
+                "x86_64" // Build all microkernels; run-time dispatch
+            } else {
+                x86_64_confname(target_features)
+            }
+        }
+
+        // BLIS does not have run-time arch detection on 32-bit ARM or PowerPC.
+        // We'll let BLIS configure determine the best match.
+        "arm" | "armv7" => "auto", // cortexa9/cortexa15
+        "aarch64" => {
+            if enabled("aarch64-generic") {
+                // Portable kernels behave the same on every ARM server generation.
+                "generic"
+            } else if enabled("runtime-dispatch") {
+                "arm64" // armsve/firestorm/thunderx2/cortexa57; run-time dispatch
+            } else {
+                "auto" // cortexa57/thunderx2
+            }
+        }
+        // `CARGO_CFG_TARGET_ARCH` is `powerpc64` for both endiannesses, but BLIS's POWER9/POWER10
+        // kernels only support little-endian, and its `power7` configuration is not registered.
+        "powerpc64" if endian == "little" => "auto", // power9/power10
+        // BLIS 0.9 has no RISC-V kernels, and `auto` would pick the build host's ones when
+        // cross-compiling.
+        "riscv64" | "riscv32" => "generic",
+        // BLIS has no s390x kernels, so the portable reference kernels are the best match.
+        "s390x" => "generic",
+        _ => "generic",
+    }
+}
+
+/// Returns the arguments that make bindgen's clang parse the headers for `target`: the target
+/// itself when cross-compiling, and the sysroot given in `cflags`.
+pub fn clang_args(host: &str, target: &str, cflags: &[String]) -> Vec<String> {
+    let mut args = Vec::new();
+    if host != target {
+        args.push(format!("--target={}", target));
+    }
+    let mut flags = cflags.iter();
+    while let Some(flag) = flags.next() {
+        if flag.starts_with("--sysroot=") {
+            args.push(flag.clone());
+        } else if flag == "--sysroot" || flag == "-isysroot" {
+            if let Some(sysroot) = flags.next() {
+                args.push(flag.clone());
+                args.push(sysroot.clone());
+            }
+        }
+    }
+    args
+}
+
+/// Returns whether `pthread_create` and friends are in a library of their own, rather than in
+/// the C library, for the target with `CARGO_CFG_TARGET_OS` and `CARGO_CFG_TARGET_ENV`.
+pub fn needs_pthread_lib(target_os: &str, target_env: &str) -> bool {
+    match target_os {
+        // musl folds pthreads into libc, and linking it separately breaks static links.
+        "linux" => target_env != "musl",
+        "freebsd" | "netbsd" | "openbsd" | "dragonfly" | "solaris" | "illumos" => true,
+        // MinGW provides pthreads through winpthreads.
+        "windows" => target_env == "gnu",
+        // Apple platforms and Android ship pthreads as part of libc.
+        _ => false,
+    }
+}
+
+/// Returns the libraries of the runtime BLIS's threads need with the `threading` model passed to
+/// `configure`. `omp_lib` names the OpenMP runtime, and is only called for `openmp`.
+pub fn threading_libs(
+    threading: &str,
+    omp_lib: impl FnOnce() -> String,
+    target_os: &str,
+    target_env: &str,
+) -> Vec<String> {
+    match threading {
+        "openmp" => vec![omp_lib()],
+        "pthreads" if needs_pthread_lib(target_os, target_env) => vec!["pthread".to_string()],
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the directives linking the shared libraries `libs`.
+pub fn link_lib_directives(libs: &[String]) -> Vec<String> {
+    libs.iter()
+        .map(|lib| format!("cargo:rustc-link-lib=dylib={}", lib))
+        .collect()
+}
+
+/// Lists the files under `dir` relative to `source`, skipping the version control metadata and
+/// the directories left out of the published crate.
+#[cfg_attr(not(feature = "verify-source"), allow(dead_code))]
+pub fn source_files(source: &Path, dir: &Path, files: &mut Vec<String>) {
+    for entry in fs::read_dir(source.join(dir)).unwrap() {
+        let entry = entry.unwrap();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = dir.join(&name);
+        let excluded = name.starts_with(".git")
+            || name == "test"
+            || dir.as_os_str().is_empty() && ["docs", "examples", "mpi_test"].contains(&&*name);
+        if excluded {
+            continue;
+        }
+        if entry.file_type().unwrap().is_dir() {
+            source_files(source, &path, files);
+        } else {
+            files.push(path.to_string_lossy().replace('\\', "/"));
+        }
+    }
+}
+
+/// Returns the files under `source` that `listed`, the files of the manifest, does not contain,
+/// skipping the same ones as [`source_files`].
+#[cfg_attr(not(feature = "verify-source"), allow(dead_code))]
+pub fn unlisted_files(source: &Path, listed: &[&str]) -> Vec<String> {
+    let mut files = Vec::new();
+    source_files(source, Path::new(""), &mut files);
+    files.sort();
+    files.retain(|file| !listed.contains(&file.as_str()));
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn split_flags_like_a_shell() {
+        assert_eq!(
+            split_flags("  -O2\t-march=native "),
+            words(&["-O2", "-march=native"])
+        );
+        assert_eq!(
+            split_flags(r#"-I"/path with space" '-DNAME=a b' -I/other\ path"#),
+            words(&["-I/path with space", "-DNAME=a b", "-I/other path"])
+        );
+        assert_eq!(split_flags(r#"-DQUOTE='"' """#), words(&["-DQUOTE=\"", ""]));
+        assert!(split_flags("").is_empty());
+    }
+
+    #[test]
+    fn split_configure_args() {
+        assert_eq!(
+            split_flags(r#"--enable-foo CFLAGS="-O2 -march=native" '--prefix=/opt/my blis'"#),
+            words(&[
+                "--enable-foo",
+                "CFLAGS=-O2 -march=native",
+                "--prefix=/opt/my blis"
+            ])
+        );
+    }
+
+    #[test]
+    fn shell_flags_quotes_special_characters() {
+        assert_eq!(
+            shell_flags(&words(&["-O2", "-I/usr/include", "--sysroot=/a,b@c"])),
+            "-O2 -I/usr/include --sysroot=/a,b@c"
+        );
+        assert_eq!(
+            shell_flags(&words(&["-I/path with space", "-DNAME='a'", ""])),
+            r#"'-I/path with space' '-DNAME='\''a'\''' ''"#
+        );
+    }
+
+    #[test]
+    fn flags_survive_the_shell() {
+        let flags = words(&["-O2", "-I/path with space", "-DNAME=\"it's\"", "-DEMPTY="]);
+        assert_eq!(split_flags(&shell_flags(&flags)), flags);
+    }
+
+    #[test]
+    fn clang_args_target_when_cross_compiling() {
+        let host = "x86_64-unknown-linux-gnu";
+        let cflags = words(&["-O2"]);
+        assert!(clang_args(host, host, &cflags).is_empty());
+        assert_eq!(
+            clang_args(host, "aarch64-unknown-linux-gnu", &cflags),
+            words(&["--target=aarch64-unknown-linux-gnu"])
+        );
+    }
+
+    #[test]
+    fn pthreads_directives() {
+        let directives = |target_os, target_env| {
+            let libs = threading_libs("pthreads", || unreachable!(), target_os, target_env);
+            link_lib_directives(&libs)
+        };
+        assert_eq!(
+            directives("linux", "gnu"),
+            words(&["cargo:rustc-link-lib=dylib=pthread"])
+        );
+        assert!(directives("linux", "musl").is_empty());
+        assert!(directives("macos", "").is_empty());
+    }
+
+    #[test]
+    fn openmp_directives() {
+        let libs = threading_libs("openmp", || "gomp".to_string(), "linux", "gnu");
+        assert_eq!(
+            link_lib_directives(&libs),
+            words(&["cargo:rustc-link-lib=dylib=gomp"])
+        );
+        assert!(threading_libs("no", || unreachable!(), "linux", "gnu").is_empty());
+    }
+
+    fn options(features: &[&str]) -> Vec<String> {
+        feature_options(|feature| features.contains(&feature))
+    }
+
+    #[test]
+    fn feature_options_by_features() {
+        // The enabled features, and the options that must and must not be passed for them.
+        let cases: &[(&[&str], &[&str], &[&str])] = &[
+            (
+                &[],
+                &[
+                    "--disable-static",
+                    "--enable-shared",
+                    "--disable-sup-handling",
+                    "--disable-trsm-preinversion",
+                    "--disable-mixed-dt",
+                    "--disable-blas",
+                ],
+                &[
+                    "--enable-aocl-dynamic",
+                    "--enable-pragma-omp-simd",
+                    "--disable-pragma-omp-simd",
+                    "--disable-mixed-dt-extra-mem",
+                    "--disable-pba-pools",
+                    "--int-size=64",
+                    "--blas-int-size=64",
+                    "--enable-blas",
+                    "--enable-cblas",
+                    "--complex-return=intel",
+                ],
+            ),
+            (
+                &["static"],
+                &["--enable-static", "--disable-shared"],
+                &["--enable-shared"],
+            ),
+            (
+                &["sup"],
+                &["--enable-sup-handling"],
+                &["--disable-sup-handling"],
+            ),
+            (
+                &["trsm-preinversion"],
+                &["--enable-trsm-preinversion"],
+                &["--disable-trsm-preinversion"],
+            ),
+            (
+                &["mixed-dt"],
+                &["--enable-mixed-dt", "--disable-mixed-dt-extra-mem"],
+                &["--disable-mixed-dt", "--enable-mixed-dt-extra-mem"],
+            ),
+            (
+                &["mixed-dt", "mixed-dt-extra-mem"],
+                &["--enable-mixed-dt", "--enable-mixed-dt-extra-mem"],
+                &["--disable-mixed-dt", "--disable-mixed-dt-extra-mem"],
+            ),
+            (
+                &["omp-simd"],
+                &["--enable-pragma-omp-simd"],
+                &["--disable-pragma-omp-simd"],
+            ),
+            (
+                &["no-omp-simd"],
+                &["--disable-pragma-omp-simd"],
+                &["--enable-pragma-omp-simd"],
+            ),
+            (
+                &["no-pools"],
+                &["--disable-pba-pools", "--disable-sba-pools"],
+                &[],
+            ),
+            (&["ilp64"], &["--int-size=64", "--blas-int-size=64"], &[]),
+            (
+                &["blas"],
+                &["--enable-blas"],
+                &["--disable-blas", "--enable-cblas"],
+            ),
+            (
+                &["cblas"],
+                &["--enable-blas", "--enable-cblas"],
+                &["--disable-blas"],
+            ),
+            (&["complex-return-intel"], &["--complex-return=intel"], &[]),
+            (
+                &["aocl-dynamic", "parallel-openmp"],
+                &["--enable-aocl-dynamic"],
+                &[],
+            ),
+            (
+                &["aocl-dynamic", "parallel-pthreads"],
+                &["--enable-aocl-dynamic"],
+                &[],
+            ),
+            (&["parallel-openmp"], &[], &["--enable-aocl-dynamic"]),
+        ];
+        for (features, expected, forbidden) in cases {
+            let options = options(features);
+            for option in *expected {
+                assert!(
+                    options.iter().any(|o| o == option),
+                    "{:?} should pass {}",
+                    features,
+                    option
+                );
+            }
+            for option in *forbidden {
+                assert!(
+                    !options.iter().any(|o| o == option),
+                    "{:?} should not pass {}",
+                    features,
+                    option
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "requires 'mixed-dt'")]
+    fn mixed_dt_extra_mem_requires_mixed_dt() {
+        options(&["mixed-dt-extra-mem"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "mutually exclusive")]
+    fn omp_simd_features_are_exclusive() {
+        options(&["omp-simd", "no-omp-simd"]);
+    }
+
+    #[test]
+    fn default_confnames() {
+        let confname = |rust_arch, endian, features: &[&str]| {
+            default_confname(rust_arch, endian, "", |feature| features.contains(&feature))
+        };
+        assert_eq!(confname("riscv64", "little", &[]), "generic");
+        assert_eq!(confname("riscv32", "little", &[]), "generic");
+        assert_eq!(confname("s390x", "big", &[]), "generic");
+        assert_eq!(confname("powerpc64", "little", &[]), "auto");
+        assert_eq!(confname("powerpc64", "big", &[]), "generic");
+        assert_eq!(confname("arm", "little", &[]), "auto");
+        assert_eq!(confname("aarch64", "little", &[]), "auto");
+        assert_eq!(
+            confname("aarch64", "little", &["runtime-dispatch"]),
+            "arm64"
+        );
+        assert_eq!(
+            confname(
+                "aarch64",
+                "little",
+                &["aarch64-generic", "runtime-dispatch"]
+            ),
+            "generic"
+        );
+        assert_eq!(
+            confname("x86_64", "little", &["runtime-dispatch"]),
+            "x86_64"
+        );
+    }
+
+    #[test]
+    fn x86_64_confnames() {
+        assert_eq!(x86_64_confname(""), "x86_64");
+        assert_eq!(x86_64_confname("fxsr,sse,sse2"), "x86_64");
+        assert_eq!(x86_64_confname("avx,sse2"), "sandybridge");
+        assert_eq!(x86_64_confname("avx,avx2"), "sandybridge");
+        assert_eq!(x86_64_confname("avx,avx2,fma"), "haswell");
+        assert_eq!(x86_64_confname("avx,avx2,avx512f,fma"), "haswell");
+        assert_eq!(
+            x86_64_confname("avx,avx2,avx512bw,avx512dq,avx512f,avx512vl,fma"),
+            "skx"
+        );
+        // Features only match whole names.
+        assert_eq!(x86_64_confname("avxvnni,avx2fake,fmax"), "x86_64");
+        assert_eq!(
+            default_confname("x86_64", "little", "avx,avx2,fma", |_| false),
+            "haswell"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "requires 'parallel-pthreads' or 'parallel-openmp'")]
+    fn aocl_dynamic_requires_threading() {
+        options(&["aocl-dynamic"]);
+    }
+
+    #[test]
+    fn clang_args_sysroot() {
+        let target = "aarch64-unknown-linux-gnu";
+        let cflags = words(&[
+            "-O2",
+            "--sysroot=/opt/sysroot",
+            "-isysroot",
+            "/opt/sdk path",
+            "--sysroot",
+            "/opt/other",
+            "-I/usr/include",
+        ]);
+        assert_eq!(
+            clang_args(target, target, &cflags),
+            words(&[
+                "--sysroot=/opt/sysroot",
+                "-isysroot",
+                "/opt/sdk path",
+                "--sysroot",
+                "/opt/other",
+            ])
+        );
+        // A trailing `--sysroot` without its value is dropped.
+        assert_eq!(
+            clang_args("x86_64-unknown-linux-gnu", target, &words(&["--sysroot"])),
+            words(&["--target=aarch64-unknown-linux-gnu"])
+        );
+    }
+
+    #[test]
+    fn pthread_lib_by_target() {
+        // The `CARGO_CFG_TARGET_OS` and `CARGO_CFG_TARGET_ENV` of each target.
+        let targets = [
+            ("x86_64-unknown-linux-gnu", "linux", "gnu", true),
+            ("aarch64-unknown-linux-gnu", "linux", "gnu", true),
+            ("x86_64-unknown-linux-musl", "linux", "musl", false),
+            ("aarch64-linux-android", "android", "", false),
+            ("x86_64-apple-darwin", "macos", "", false),
+            ("aarch64-apple-ios", "ios", "", false),
+            ("x86_64-unknown-freebsd", "freebsd", "", true),
+            ("x86_64-unknown-netbsd", "netbsd", "", true),
+            ("x86_64-unknown-openbsd", "openbsd", "", true),
+            ("x86_64-unknown-dragonfly", "dragonfly", "", true),
+            ("x86_64-pc-solaris", "solaris", "", true),
+            ("x86_64-unknown-illumos", "illumos", "", true),
+            ("x86_64-pc-windows-gnu", "windows", "gnu", true),
+            ("x86_64-pc-windows-msvc", "windows", "msvc", false),
+            ("wasm32-wasip1", "wasi", "p1", false),
+        ];
+        for (target, target_os, target_env, expected) in targets {
+            assert_eq!(
+                needs_pthread_lib(target_os, target_env),
+                expected,
+                "{}",
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn flags_args_quote_spaces() {
+        let cflags = split_flags(r#"-O2 -I"/path with space""#);
+        assert_eq!(
+            flags_arg("CFLAGS", &cflags).unwrap(),
+            "CFLAGS=-O2 '-I/path with space'"
+        );
+        assert_eq!(flags_arg("LDFLAGS", &[]), None);
+        assert_eq!(
+            cflags_preset_arg("-O2 '-I/path with space'", "-g -I'/other path'"),
+            "CFLAGS_PRESET=-O2 '-I/path with space' -g '-I/other path'"
+        );
+        assert_eq!(cflags_preset_arg("-O2", ""), "CFLAGS_PRESET=-O2");
+    }
+
+    #[test]
+    fn unlisted_source_files() {
+        let source = std::env::temp_dir().join(format!("blis-sys-sources-{}", std::process::id()));
+        for dir in [
+            "frame/base",
+            "kernels",
+            ".git",
+            "test",
+            "docs",
+            "config/test",
+        ] {
+            fs::create_dir_all(source.join(dir)).unwrap();
+        }
+        for file in [
+            "configure",
+            "frame/base/bli_init.c",
+            "frame/base/bli_extra.c",
+            "kernels/extra.c",
+            ".git/HEAD",
+            ".gitignore",
+            "test/test.c",
+            "docs/README.md",
+            "config/test/bli_test.c",
+        ] {
+            fs::write(source.join(file), "").unwrap();
+        }
+        let unlisted = unlisted_files(&source, &["configure", "frame/base/bli_init.c"]);
+        fs::remove_dir_all(&source).unwrap();
+        assert_eq!(
+            unlisted,
+            words(&["frame/base/bli_extra.c", "kernels/extra.c"])
+        );
+    }
+}
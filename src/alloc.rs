@@ -0,0 +1,22 @@
+//! Routing BLIS's memory allocations through custom functions.
+
+use crate::size_t;
+use std::os::raw::c_void;
+
+/// Allocates `size` bytes, returning null on failure. BLIS aligns the blocks it needs itself.
+pub type MallocFn = unsafe extern "C" fn(size: size_t) -> *mut c_void;
+
+/// Frees a block returned by the matching [`MallocFn`].
+pub type FreeFn = unsafe extern "C" fn(ptr: *mut c_void);
+
+/// Makes BLIS allocate its packing pools, its internal structures and the buffers of
+/// [`Obj::new`](crate::object::Obj::new) through `malloc` and `free`.
+///
+/// # Safety
+/// Must be called before BLIS allocates anything, i.e. before any other BLIS function or
+/// [`Blis::new`](crate::runtime::Blis::new), since blocks obtained from the previous allocator
+/// would be released through the new `free`. Both functions may be called concurrently from
+/// BLIS's threads.
+pub unsafe fn set_allocator(malloc: MallocFn, free: FreeFn) {
+    crate::bli_sys_set_allocator(Some(malloc), Some(free))
+}
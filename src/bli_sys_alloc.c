@@ -0,0 +1,30 @@
+/*
+   Allocator hooks added to the BLIS sources by blis-sys when the `custom-alloc` feature is
+   enabled. BLIS_MALLOC_POOL, BLIS_MALLOC_INTL and BLIS_MALLOC_USER (and their BLIS_FREE_*
+   counterparts) are defined to the functions below.
+*/
+
+#include "blis.h"
+
+static malloc_ft bli_sys_malloc_fp = malloc;
+static free_ft   bli_sys_free_fp   = free;
+
+void* bli_sys_malloc( size_t size )
+{
+	return bli_sys_malloc_fp( size );
+}
+
+void bli_sys_free( void* p )
+{
+	bli_sys_free_fp( p );
+}
+
+void bli_sys_set_allocator
+     (
+       void* (*malloc_fp)( size_t ),
+       void  (*free_fp)( void* )
+     )
+{
+	bli_sys_malloc_fp = malloc_fp;
+	bli_sys_free_fp   = free_fp;
+}
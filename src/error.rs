@@ -0,0 +1,145 @@
+//! Errors reported by the safe wrappers before calling into BLIS.
+
+use crate::{dim_t, inc_t};
+use std::fmt;
+
+/// An invalid combination of buffers, dimensions and strides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A buffer is shorter than its dimensions and strides require.
+    BufferTooShort {
+        operand: &'static str,
+        len: usize,
+        required: usize,
+    },
+    /// A leading dimension is smaller than the number of rows of the matrix.
+    LeadingDimensionTooSmall {
+        operand: &'static str,
+        ld: usize,
+        rows: usize,
+    },
+    /// A dimension of an operand does not match the other operands.
+    DimensionMismatch {
+        operand: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    /// A stride is zero, so that distinct elements would alias.
+    ZeroStride { operand: &'static str },
+    /// A dimension or stride does not fit in the integer types used by BLIS.
+    Overflow,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::BufferTooShort {
+                operand,
+                len,
+                required,
+            } => write!(
+                f,
+                "`{}` has {} elements, but at least {} are required",
+                operand, len, required
+            ),
+            Error::LeadingDimensionTooSmall { operand, ld, rows } => write!(
+                f,
+                "the leading dimension of `{}` is {}, but it has {} rows",
+                operand, ld, rows
+            ),
+            Error::DimensionMismatch {
+                operand,
+                expected,
+                found,
+            } => write!(
+                f,
+                "`{}` has a dimension of {}, but {} is required",
+                operand, found, expected
+            ),
+            Error::ZeroStride { operand } => write!(f, "`{}` has a stride of zero", operand),
+            Error::Overflow => f.write_str("a dimension or stride is too large for BLIS"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub(crate) fn to_dim(value: usize) -> Result<dim_t, Error> {
+    dim_t::try_from(value).map_err(|_| Error::Overflow)
+}
+
+pub(crate) fn to_inc(value: usize) -> Result<inc_t, Error> {
+    inc_t::try_from(value).map_err(|_| Error::Overflow)
+}
+
+/// Checks that a column-major `rows` by `cols` matrix with leading dimension `ld` fits in `len`
+/// elements.
+pub(crate) fn check_matrix(
+    operand: &'static str,
+    len: usize,
+    rows: usize,
+    cols: usize,
+    ld: usize,
+) -> Result<(), Error> {
+    if ld < rows.max(1) {
+        return Err(Error::LeadingDimensionTooSmall { operand, ld, rows });
+    }
+    let required = if rows == 0 || cols == 0 {
+        0
+    } else {
+        ld.checked_mul(cols - 1)
+            .and_then(|offset| offset.checked_add(rows))
+            .ok_or(Error::Overflow)?
+    };
+    if len < required {
+        return Err(Error::BufferTooShort {
+            operand,
+            len,
+            required,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that a `rows` by `cols` matrix with row stride `rs` and column stride `cs` fits in
+/// `len` elements.
+pub(crate) fn check_strided(
+    operand: &'static str,
+    len: usize,
+    rows: usize,
+    cols: usize,
+    rs: usize,
+    cs: usize,
+) -> Result<(), Error> {
+    if rs == 0 || cs == 0 {
+        return Err(Error::ZeroStride { operand });
+    }
+    let required = if rows == 0 || cols == 0 {
+        0
+    } else {
+        (rows - 1)
+            .checked_mul(rs)
+            .zip((cols - 1).checked_mul(cs))
+            .and_then(|(row_offset, col_offset)| row_offset.checked_add(col_offset))
+            .and_then(|offset| offset.checked_add(1))
+            .ok_or(Error::Overflow)?
+    };
+    if len < required {
+        return Err(Error::BufferTooShort {
+            operand,
+            len,
+            required,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that a vector of `n` elements with increment `inc` fits in `len` elements.
+pub(crate) fn check_vector(
+    operand: &'static str,
+    len: usize,
+    n: usize,
+    inc: usize,
+) -> Result<(), Error> {
+    check_strided(operand, len, n, 1, inc, 1)
+}
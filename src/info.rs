@@ -0,0 +1,98 @@
+//! Queries describing how the linked BLIS library was configured.
+
+use crate::arch_t;
+use crate::types::DataType;
+use std::ffi::CStr;
+use std::fmt;
+use std::os::raw::c_char;
+
+fn static_str(ptr: *const c_char) -> &'static str {
+    // BLIS returns pointers to string literals, which live for the whole program.
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .expect("BLIS returned a string that is not UTF-8")
+}
+
+/// Returns the version of the linked BLIS library.
+pub fn version() -> &'static str {
+    static_str(unsafe { crate::bli_info_get_version_str() })
+}
+
+/// Returns the size in bits of BLIS's integer type, `gint_t`.
+pub fn int_type_size() -> usize {
+    unsafe { crate::bli_info_get_int_type_size() as usize }
+}
+
+/// Returns whether BLIS was built with OpenMP threading.
+pub fn openmp_enabled() -> bool {
+    unsafe { crate::bli_info_get_enable_openmp() != 0 }
+}
+
+/// Returns whether BLIS was built with pthreads threading.
+pub fn pthreads_enabled() -> bool {
+    unsafe { crate::bli_info_get_enable_pthreads() != 0 }
+}
+
+/// The default blocksizes of the active sub-configuration for one datatype.
+///
+/// The microkernel computes an `mr` by `nr` block of the output. The operations around it
+/// partition the `m`, `k` and `n` dimensions into blocks of `mc`, `kc` and `nc` to fit the caches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Blocksizes {
+    pub mr: usize,
+    pub nr: usize,
+    pub mc: usize,
+    pub kc: usize,
+    pub nc: usize,
+}
+
+/// Returns the blocksizes BLIS uses for `dt` on this machine, which with runtime dispatch are
+/// those of the sub-configuration detected for the CPU.
+pub fn blocksizes(dt: DataType) -> Blocksizes {
+    let dt = crate::num_t::from(dt).0 as usize;
+    // `bli_cntx_get_blksz_def_dt` is an inline function, which has no binding, so read the
+    // default blocksizes from the context like it does.
+    let cntx = unsafe { &*crate::bli_gks_query_cntx() };
+    let get = |id: crate::bszid_t| cntx.blkszs[id.0 as usize].v[dt] as usize;
+    Blocksizes {
+        mr: get(crate::bszid_t::BLIS_MR),
+        nr: get(crate::bszid_t::BLIS_NR),
+        mc: get(crate::bszid_t::BLIS_MC),
+        kc: get(crate::bszid_t::BLIS_KC),
+        nc: get(crate::bszid_t::BLIS_NC),
+    }
+}
+
+/// Returns the name of the sub-configuration BLIS selected for this machine.
+pub fn config_name() -> &'static str {
+    arch().name()
+}
+
+/// A sub-configuration, i.e. a set of kernels BLIS can run with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Arch(pub arch_t);
+
+impl Arch {
+    /// Returns BLIS's name for the sub-configuration, e.g. `haswell`.
+    pub fn name(self) -> &'static str {
+        static_str(unsafe { crate::bli_arch_string(self.0) })
+    }
+
+    /// Returns whether this is the portable reference configuration, which a build with
+    /// runtime dispatch falls back to on CPUs none of its kernels support.
+    pub fn is_generic(self) -> bool {
+        self.name() == "generic"
+    }
+}
+
+impl fmt::Display for Arch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Returns the sub-configuration BLIS runs with on this machine. With runtime dispatch, this is
+/// the one detected for the CPU; otherwise it is the one BLIS was built for.
+pub fn arch() -> Arch {
+    Arch(unsafe { crate::bli_arch_query_id() })
+}
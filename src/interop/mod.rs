@@ -0,0 +1,4 @@
+//! Integrations with other crates of the Rust ecosystem.
+
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
@@ -0,0 +1,118 @@
+//! Matrix multiplication of `ndarray` arrays.
+//!
+//! BLIS accepts arbitrary row and column strides, so views are passed to it in place, whatever
+//! their layout. Only views with a zero or negative stride along an axis longer than one are
+//! copied first.
+
+use crate::error::{to_dim, Error};
+use crate::inc_t;
+use crate::types::Trans;
+use ::ndarray::{Array2, ArrayView2, CowArray, Ix2};
+
+/// Returns the strides to pass to BLIS for an array of shape `dim`. ndarray gives axes of length
+/// one arbitrary strides, even zero, so they get the stride of a step past the other axis, which
+/// BLIS's checks accept.
+fn normalized_strides(dim: (usize, usize), strides: &[isize]) -> [isize; 2] {
+    let (m, n) = dim;
+    let (rs, cs) = (strides[0], strides[1]);
+    match (m <= 1, n <= 1) {
+        (true, true) => [1, 1],
+        (true, false) => [cs.saturating_mul(n as isize), cs],
+        (false, true) => [rs, rs.saturating_mul(m as isize)],
+        (false, false) => [rs, cs],
+    }
+}
+
+fn blis_compatible<'a>(a: &'a ArrayView2<'_, f64>) -> (CowArray<'a, f64, Ix2>, [isize; 2]) {
+    let strides = normalized_strides(a.dim(), a.strides());
+    if strides.iter().all(|&stride| stride > 0) {
+        (CowArray::from(a.view()), strides)
+    } else {
+        let a = a.as_standard_layout();
+        let strides = normalized_strides(a.dim(), a.strides());
+        (a, strides)
+    }
+}
+
+fn strides(strides: &[isize]) -> Result<(inc_t, inc_t), Error> {
+    let rs = inc_t::try_from(strides[0]).map_err(|_| Error::Overflow)?;
+    let cs = inc_t::try_from(strides[1]).map_err(|_| Error::Overflow)?;
+    Ok((rs, cs))
+}
+
+/// Computes the matrix product `a * b` with `bli_dgemm`.
+pub fn gemm(a: ArrayView2<'_, f64>, b: ArrayView2<'_, f64>) -> Result<Array2<f64>, Error> {
+    let (m, k) = a.dim();
+    let (b_rows, n) = b.dim();
+    if b_rows != k {
+        return Err(Error::DimensionMismatch {
+            operand: "b",
+            expected: k,
+            found: b_rows,
+        });
+    }
+    let mut c = Array2::zeros((m, n));
+    if m == 0 || n == 0 || k == 0 {
+        return Ok(c);
+    }
+    let ((a, a_strides), (b, b_strides)) = (blis_compatible(&a), blis_compatible(&b));
+    let (rsa, csa) = strides(&a_strides)?;
+    let (rsb, csb) = strides(&b_strides)?;
+    let (rsc, csc) = strides(&normalized_strides(c.dim(), c.strides()))?;
+    let (m, n, k) = (to_dim(m)?, to_dim(n)?, to_dim(k)?);
+    let (mut alpha, mut beta) = (1.0, 0.0);
+    unsafe {
+        crate::bli_dgemm(
+            Trans::NoTranspose.into(),
+            Trans::NoTranspose.into(),
+            m,
+            n,
+            k,
+            &mut alpha,
+            a.as_ptr() as *mut f64,
+            rsa,
+            csa,
+            b.as_ptr() as *mut f64,
+            rsb,
+            csb,
+            &mut beta,
+            c.as_mut_ptr(),
+            rsc,
+            csc,
+        )
+    };
+    Ok(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::ndarray::{array, s, Axis};
+
+    #[test]
+    fn length_one_axes_are_not_copied() {
+        let a = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+        let x = array![1.0, 2.0, 3.0];
+        // A single row, and a row broadcast from a vector, whose row stride is zero.
+        let row = a.slice(s![1..2, ..]);
+        let broadcast = x.broadcast((1, 3)).unwrap();
+        for view in [row, broadcast] {
+            let (compatible, strides) = blis_compatible(&view);
+            assert!(compatible.is_view());
+            assert!(strides.iter().all(|&stride| stride > 0));
+        }
+        let column = x.view().insert_axis(Axis(1));
+        assert_eq!(gemm(row, column).unwrap(), array![[32.0]]);
+        assert_eq!(gemm(broadcast, column).unwrap(), array![[14.0]]);
+        assert_eq!(gemm(column, row).unwrap(), column.dot(&row));
+    }
+
+    #[test]
+    fn broadcast_axes_are_copied() {
+        let x = array![1.0, 2.0, 3.0];
+        let a = x.broadcast((2, 3)).unwrap();
+        assert!(!blis_compatible(&a).0.is_view());
+        let ones = array![[1.0], [1.0], [1.0]];
+        assert_eq!(gemm(a, ones.view()).unwrap(), array![[6.0], [6.0]]);
+    }
+}
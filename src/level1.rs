@@ -0,0 +1,151 @@
+//! Slice-based wrappers over the level-1 vector operations.
+//!
+//! A vector of length `n` with increment `inc` occupies the elements `0, inc, ..., (n - 1) * inc`
+//! of its slice.
+
+use crate::error::{check_vector, to_dim, to_inc, Error};
+use crate::types::Conj;
+use crate::{dcomplex, scomplex};
+use std::mem::MaybeUninit;
+
+macro_rules! level1 {
+    ($T:ty, $axpy:ident, $dot:ident, $scal:ident, $copy:ident, $swap:ident,
+     $axpyv:ident, $dotv:ident, $scalv:ident, $copyv:ident, $swapv:ident) => {
+        #[doc = concat!("Computes `y := y + alpha * conj_x(x)` with `", stringify!($axpyv), "`.")]
+        pub fn $axpy(
+            conj_x: Conj,
+            n: usize,
+            alpha: $T,
+            x: &[$T],
+            incx: usize,
+            y: &mut [$T],
+            incy: usize,
+        ) -> Result<(), Error> {
+            check_vector("x", x.len(), n, incx)?;
+            check_vector("y", y.len(), n, incy)?;
+            let (n, incx, incy) = (to_dim(n)?, to_inc(incx)?, to_inc(incy)?);
+            let mut alpha = alpha;
+            unsafe {
+                crate::$axpyv(
+                    conj_x.into(),
+                    n,
+                    &mut alpha,
+                    x.as_ptr() as *mut $T,
+                    incx,
+                    y.as_mut_ptr(),
+                    incy,
+                )
+            };
+            Ok(())
+        }
+
+        #[doc = concat!("Returns the dot product of `conj_x(x)` and `conj_y(y)` with `", stringify!($dotv), "`.")]
+        pub fn $dot(
+            conj_x: Conj,
+            conj_y: Conj,
+            n: usize,
+            x: &[$T],
+            incx: usize,
+            y: &[$T],
+            incy: usize,
+        ) -> Result<$T, Error> {
+            check_vector("x", x.len(), n, incx)?;
+            check_vector("y", y.len(), n, incy)?;
+            let (n, incx, incy) = (to_dim(n)?, to_inc(incx)?, to_inc(incy)?);
+            let mut rho = MaybeUninit::<$T>::uninit();
+            unsafe {
+                crate::$dotv(
+                    conj_x.into(),
+                    conj_y.into(),
+                    n,
+                    x.as_ptr() as *mut $T,
+                    incx,
+                    y.as_ptr() as *mut $T,
+                    incy,
+                    rho.as_mut_ptr(),
+                );
+                Ok(rho.assume_init())
+            }
+        }
+
+        #[doc = concat!("Computes `x := conj_alpha(alpha) * x` with `", stringify!($scalv), "`.")]
+        pub fn $scal(
+            conj_alpha: Conj,
+            n: usize,
+            alpha: $T,
+            x: &mut [$T],
+            incx: usize,
+        ) -> Result<(), Error> {
+            check_vector("x", x.len(), n, incx)?;
+            let (n, incx) = (to_dim(n)?, to_inc(incx)?);
+            let mut alpha = alpha;
+            unsafe {
+                crate::$scalv(
+                    conj_alpha.into(),
+                    n,
+                    &mut alpha,
+                    x.as_mut_ptr(),
+                    incx,
+                )
+            };
+            Ok(())
+        }
+
+        #[doc = concat!("Computes `y := conj_x(x)` with `", stringify!($copyv), "`.")]
+        pub fn $copy(
+            conj_x: Conj,
+            n: usize,
+            x: &[$T],
+            incx: usize,
+            y: &mut [$T],
+            incy: usize,
+        ) -> Result<(), Error> {
+            check_vector("x", x.len(), n, incx)?;
+            check_vector("y", y.len(), n, incy)?;
+            let (n, incx, incy) = (to_dim(n)?, to_inc(incx)?, to_inc(incy)?);
+            unsafe {
+                crate::$copyv(
+                    conj_x.into(),
+                    n,
+                    x.as_ptr() as *mut $T,
+                    incx,
+                    y.as_mut_ptr(),
+                    incy,
+                )
+            };
+            Ok(())
+        }
+
+        #[doc = concat!("Swaps the elements of `x` and `y` with `", stringify!($swapv), "`.")]
+        pub fn $swap(
+            n: usize,
+            x: &mut [$T],
+            incx: usize,
+            y: &mut [$T],
+            incy: usize,
+        ) -> Result<(), Error> {
+            check_vector("x", x.len(), n, incx)?;
+            check_vector("y", y.len(), n, incy)?;
+            let (n, incx, incy) = (to_dim(n)?, to_inc(incx)?, to_inc(incy)?);
+            unsafe { crate::$swapv(n, x.as_mut_ptr(), incx, y.as_mut_ptr(), incy) };
+            Ok(())
+        }
+    };
+}
+
+level1!(
+    f32, saxpy, sdot, sscal, scopy, sswap, bli_saxpyv, bli_sdotv, bli_sscalv, bli_scopyv,
+    bli_sswapv
+);
+level1!(
+    f64, daxpy, ddot, dscal, dcopy, dswap, bli_daxpyv, bli_ddotv, bli_dscalv, bli_dcopyv,
+    bli_dswapv
+);
+level1!(
+    scomplex, caxpy, cdot, cscal, ccopy, cswap, bli_caxpyv, bli_cdotv, bli_cscalv, bli_ccopyv,
+    bli_cswapv
+);
+level1!(
+    dcomplex, zaxpy, zdot, zscal, zcopy, zswap, bli_zaxpyv, bli_zdotv, bli_zscalv, bli_zcopyv,
+    bli_zswapv
+);
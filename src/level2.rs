@@ -0,0 +1,138 @@
+//! Slice-based wrappers over the level-2 matrix-vector operations.
+//!
+//! Matrices are column-major with a leading dimension, as in [`level3`](crate::level3), and
+//! vectors have an increment, as in [`level1`](crate::level1).
+
+use crate::error::{check_matrix, check_vector, to_dim, to_inc, Error};
+use crate::types::{Conj, Diag, Trans, Uplo};
+use crate::{dcomplex, scomplex};
+
+macro_rules! level2 {
+    ($T:ty, $gemv:ident, $ger:ident, $trsv:ident,
+     $gemv_raw:ident, $ger_raw:ident, $trsv_raw:ident) => {
+        #[doc = concat!("Computes `y := beta * y + alpha * op(a) * conj_x(x)` with `", stringify!($gemv_raw), "`, where")]
+        /// `a` is `m` by `n`.
+        #[allow(clippy::too_many_arguments)]
+        pub fn $gemv(
+            trans_a: Trans,
+            conj_x: Conj,
+            m: usize,
+            n: usize,
+            alpha: $T,
+            a: &[$T],
+            lda: usize,
+            x: &[$T],
+            incx: usize,
+            beta: $T,
+            y: &mut [$T],
+            incy: usize,
+        ) -> Result<(), Error> {
+            let (x_len, y_len) = if trans_a.is_transposed() { (m, n) } else { (n, m) };
+            check_matrix("a", a.len(), m, n, lda)?;
+            check_vector("x", x.len(), x_len, incx)?;
+            check_vector("y", y.len(), y_len, incy)?;
+            let (m, n, lda) = (to_dim(m)?, to_dim(n)?, to_inc(lda)?);
+            let (incx, incy) = (to_inc(incx)?, to_inc(incy)?);
+            let (mut alpha, mut beta) = (alpha, beta);
+            unsafe {
+                crate::$gemv_raw(
+                    trans_a.into(),
+                    conj_x.into(),
+                    m,
+                    n,
+                    &mut alpha,
+                    a.as_ptr() as *mut $T,
+                    1,
+                    lda,
+                    x.as_ptr() as *mut $T,
+                    incx,
+                    &mut beta,
+                    y.as_mut_ptr(),
+                    incy,
+                )
+            };
+            Ok(())
+        }
+
+        #[doc = concat!("Computes `a := a + alpha * conj_x(x) * conj_y(y)^T` with `", stringify!($ger_raw), "`, where")]
+        /// `a` is `m` by `n`.
+        #[allow(clippy::too_many_arguments)]
+        pub fn $ger(
+            conj_x: Conj,
+            conj_y: Conj,
+            m: usize,
+            n: usize,
+            alpha: $T,
+            x: &[$T],
+            incx: usize,
+            y: &[$T],
+            incy: usize,
+            a: &mut [$T],
+            lda: usize,
+        ) -> Result<(), Error> {
+            check_vector("x", x.len(), m, incx)?;
+            check_vector("y", y.len(), n, incy)?;
+            check_matrix("a", a.len(), m, n, lda)?;
+            let (m, n, lda) = (to_dim(m)?, to_dim(n)?, to_inc(lda)?);
+            let (incx, incy) = (to_inc(incx)?, to_inc(incy)?);
+            let mut alpha = alpha;
+            unsafe {
+                crate::$ger_raw(
+                    conj_x.into(),
+                    conj_y.into(),
+                    m,
+                    n,
+                    &mut alpha,
+                    x.as_ptr() as *mut $T,
+                    incx,
+                    y.as_ptr() as *mut $T,
+                    incy,
+                    a.as_mut_ptr(),
+                    1,
+                    lda,
+                )
+            };
+            Ok(())
+        }
+
+        #[doc = concat!("Solves `op(a) * x' = alpha * x` and stores `x'` in `x` with `", stringify!($trsv_raw), "`, where")]
+        /// `a` is an `m` by `m` triangular matrix stored in the `uplo_a` triangle.
+        #[allow(clippy::too_many_arguments)]
+        pub fn $trsv(
+            uplo_a: Uplo,
+            trans_a: Trans,
+            diag_a: Diag,
+            m: usize,
+            alpha: $T,
+            a: &[$T],
+            lda: usize,
+            x: &mut [$T],
+            incx: usize,
+        ) -> Result<(), Error> {
+            check_matrix("a", a.len(), m, m, lda)?;
+            check_vector("x", x.len(), m, incx)?;
+            let (m, lda, incx) = (to_dim(m)?, to_inc(lda)?, to_inc(incx)?);
+            let mut alpha = alpha;
+            unsafe {
+                crate::$trsv_raw(
+                    uplo_a.into(),
+                    trans_a.into(),
+                    diag_a.into(),
+                    m,
+                    &mut alpha,
+                    a.as_ptr() as *mut $T,
+                    1,
+                    lda,
+                    x.as_mut_ptr(),
+                    incx,
+                )
+            };
+            Ok(())
+        }
+    };
+}
+
+level2!(f32, sgemv, sger, strsv, bli_sgemv, bli_sger, bli_strsv);
+level2!(f64, dgemv, dger, dtrsv, bli_dgemv, bli_dger, bli_dtrsv);
+level2!(scomplex, cgemv, cger, ctrsv, bli_cgemv, bli_cger, bli_ctrsv);
+level2!(dcomplex, zgemv, zger, ztrsv, bli_zgemv, bli_zger, bli_ztrsv);
@@ -0,0 +1,65 @@
+//! Slice-based wrappers over the level-3 operations.
+//!
+//! Matrices are column-major with a leading dimension, as in the reference BLAS.
+
+use crate::error::{check_matrix, to_dim, to_inc, Error};
+use crate::types::Trans;
+use crate::{dcomplex, scomplex};
+
+macro_rules! gemm {
+    ($name:ident, $T:ty, $raw:ident) => {
+        #[doc = concat!("Computes `c := beta * c + alpha * op(a) * op(b)` with `", stringify!($raw), "`, where")]
+        /// `op(a)` is `m` by `k`, `op(b)` is `k` by `n` and `c` is `m` by `n`.
+        #[allow(clippy::too_many_arguments)]
+        pub fn $name(
+            trans_a: Trans,
+            trans_b: Trans,
+            m: usize,
+            n: usize,
+            k: usize,
+            alpha: $T,
+            a: &[$T],
+            lda: usize,
+            b: &[$T],
+            ldb: usize,
+            beta: $T,
+            c: &mut [$T],
+            ldc: usize,
+        ) -> Result<(), Error> {
+            let (a_rows, a_cols) = if trans_a.is_transposed() { (k, m) } else { (m, k) };
+            let (b_rows, b_cols) = if trans_b.is_transposed() { (n, k) } else { (k, n) };
+            check_matrix("a", a.len(), a_rows, a_cols, lda)?;
+            check_matrix("b", b.len(), b_rows, b_cols, ldb)?;
+            check_matrix("c", c.len(), m, n, ldc)?;
+            let (m, n, k) = (to_dim(m)?, to_dim(n)?, to_dim(k)?);
+            let (lda, ldb, ldc) = (to_inc(lda)?, to_inc(ldb)?, to_inc(ldc)?);
+            let (mut alpha, mut beta) = (alpha, beta);
+            unsafe {
+                crate::$raw(
+                    trans_a.into(),
+                    trans_b.into(),
+                    m,
+                    n,
+                    k,
+                    &mut alpha,
+                    a.as_ptr() as *mut $T,
+                    1,
+                    lda,
+                    b.as_ptr() as *mut $T,
+                    1,
+                    ldb,
+                    &mut beta,
+                    c.as_mut_ptr(),
+                    1,
+                    ldc,
+                )
+            };
+            Ok(())
+        }
+    };
+}
+
+gemm!(sgemm, f32, bli_sgemm);
+gemm!(dgemm, f64, bli_dgemm);
+gemm!(cgemm, scomplex, bli_cgemm);
+gemm!(zgemm, dcomplex, bli_zgemm);
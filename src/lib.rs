@@ -1,8 +1,107 @@
+//! Rust bindings for BLIS, with safe wrappers over parts of it.
+//!
+//! # Safety and checks
+//! The safe wrappers check the slices they are given against the dimensions, strides and
+//! increments before calling into BLIS, and return an [`Error`](error::Error) instead when they
+//! do not fit, so that BLIS never reads or writes out of bounds.
+
 #![allow(non_upper_case_globals)]
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
-include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+/// The generated bindings, also re-exported at the crate root.
+pub mod raw {
+    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+}
+
+pub use raw::*;
+
+/// Version of the BLIS library the bindings were generated for.
+pub const VERSION: &str = env!("BLIS_SYS_VERSION");
+
+/// How BLIS was obtained and configured, as JSON: its version, source (`vendored`, `system` or
+/// `prebuilt`), sub-configuration, threading model, link kind, integer size and compiler settings.
+pub const BUILD_INFO: &str = include_str!(env!("BLIS_SYS_BUILD_INFO"));
+
+/// Path of BLIS's test driver, `test_libblis.x`, next to its default input files.
+#[cfg(feature = "build-testsuite")]
+pub const TESTSUITE: &str = env!("BLIS_SYS_TESTSUITE");
+
+pub mod error;
+pub mod info;
+pub mod interop;
+pub mod level1;
+pub mod level2;
+pub mod level3;
+pub mod object;
+pub mod runtime;
+pub mod types;
+pub mod util;
 
-#[cfg(feature = "openmp")]
+#[cfg(any(feature = "parallel-pthreads", feature = "parallel-openmp"))]
+pub mod threading;
+
+#[cfg(feature = "custom-alloc")]
+pub mod alloc;
+
+#[cfg(feature = "parallel-openmp")]
 extern crate openmp_sys;
+
+#[cfg(test)]
+mod tests {
+    use crate::{dim_t, inc_t, obj_t, trans_t};
+
+    #[test]
+    fn bindings_declare_core_items() {
+        // Broken bindings fail to compile this rather than code downstream.
+        type Gemm = unsafe extern "C" fn(
+            trans_t,
+            trans_t,
+            dim_t,
+            dim_t,
+            dim_t,
+            *mut f64,
+            *mut f64,
+            inc_t,
+            inc_t,
+            *mut f64,
+            inc_t,
+            inc_t,
+            *mut f64,
+            *mut f64,
+            inc_t,
+            inc_t,
+        );
+        let _: Gemm = crate::bli_dgemm;
+        assert!(std::mem::size_of::<obj_t>() > 0);
+    }
+
+    #[cfg(feature = "cblas")]
+    #[test]
+    fn cblas_dgemm() {
+        use crate::{CBLAS_ORDER, CBLAS_TRANSPOSE};
+
+        let a = [1.0, 2.0, 3.0, 4.0];
+        let b = [5.0, 6.0, 7.0, 8.0];
+        let mut c = [0.0; 4];
+        unsafe {
+            crate::cblas_dgemm(
+                CBLAS_ORDER::CblasRowMajor,
+                CBLAS_TRANSPOSE::CblasNoTrans,
+                CBLAS_TRANSPOSE::CblasNoTrans,
+                2,
+                2,
+                2,
+                1.0,
+                a.as_ptr(),
+                2,
+                b.as_ptr(),
+                2,
+                0.0,
+                c.as_mut_ptr(),
+                2,
+            )
+        };
+        assert_eq!(c, [19.0, 22.0, 43.0, 50.0]);
+    }
+}
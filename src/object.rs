@@ -0,0 +1,113 @@
+//! An owning wrapper for objects of the object API.
+
+use crate::error::{check_strided, to_dim, to_inc, Error};
+use crate::obj_t;
+use crate::types::{DataType, Element};
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+
+/// A BLIS object, either owning its buffer or borrowing one for `'a`.
+///
+/// The `obj_t` is boxed, since BLIS stores a pointer to the object inside of it.
+pub struct Obj<'a> {
+    raw: Box<obj_t>,
+    dt: DataType,
+    owned: bool,
+    _buffer: PhantomData<&'a mut [u8]>,
+}
+
+fn create(init: impl FnOnce(*mut obj_t)) -> Box<obj_t> {
+    let raw = Box::into_raw(Box::new(MaybeUninit::<obj_t>::uninit())).cast::<obj_t>();
+    init(raw);
+    unsafe { Box::from_raw(raw) }
+}
+
+impl Obj<'static> {
+    /// Creates an `m` by `n` object with a buffer allocated by BLIS.
+    ///
+    /// Passing zero for both `rs` and `cs` lets BLIS choose column-major storage.
+    pub fn new(dt: DataType, m: usize, n: usize, rs: usize, cs: usize) -> Result<Self, Error> {
+        if (rs == 0) != (cs == 0) {
+            return Err(Error::ZeroStride { operand: "obj" });
+        }
+        let (m, n, rs, cs) = (to_dim(m)?, to_dim(n)?, to_inc(rs)?, to_inc(cs)?);
+        let raw = create(|obj| unsafe { crate::bli_obj_create(dt.into(), m, n, rs, cs, obj) });
+        Ok(Obj {
+            raw,
+            dt,
+            owned: true,
+            _buffer: PhantomData,
+        })
+    }
+}
+
+impl<'a> Obj<'a> {
+    /// Creates an `m` by `n` object viewing `buffer` with row stride `rs` and column stride `cs`.
+    pub fn with_buffer<T: Element>(
+        buffer: &'a mut [T],
+        m: usize,
+        n: usize,
+        rs: usize,
+        cs: usize,
+    ) -> Result<Self, Error> {
+        check_strided("buffer", buffer.len(), m, n, rs, cs)?;
+        let (m, n, rs, cs) = (to_dim(m)?, to_dim(n)?, to_inc(rs)?, to_inc(cs)?);
+        let p = buffer.as_mut_ptr().cast();
+        let raw = create(|obj| unsafe {
+            crate::bli_obj_create_with_attached_buffer(T::DATA_TYPE.into(), m, n, p, rs, cs, obj)
+        });
+        Ok(Obj {
+            raw,
+            dt: T::DATA_TYPE,
+            owned: false,
+            _buffer: PhantomData,
+        })
+    }
+
+    /// Creates an `m` by `n` object viewing a row-major `buffer`, with row stride `n` and column
+    /// stride 1.
+    pub fn row_major<T: Element>(buffer: &'a mut [T], m: usize, n: usize) -> Result<Self, Error> {
+        Self::with_buffer(buffer, m, n, n.max(1), 1)
+    }
+
+    /// Creates an `m` by `n` object viewing a column-major `buffer`, as in the reference BLAS,
+    /// with row stride 1 and column stride `m`.
+    pub fn col_major<T: Element>(buffer: &'a mut [T], m: usize, n: usize) -> Result<Self, Error> {
+        Self::with_buffer(buffer, m, n, 1, m.max(1))
+    }
+
+    /// Returns the datatype of the elements.
+    pub fn datatype(&self) -> DataType {
+        self.dt
+    }
+
+    /// Returns the number of rows, like `bli_obj_length`.
+    pub fn length(&self) -> usize {
+        // `bli_obj_length` is an inline function, which has no binding.
+        self.raw.dim[0] as usize
+    }
+
+    /// Returns the number of columns, like `bli_obj_width`.
+    pub fn width(&self) -> usize {
+        self.raw.dim[1] as usize
+    }
+
+    /// Returns a pointer to the underlying object, for passing to the `bli_*` functions.
+    pub fn as_ptr(&self) -> *const obj_t {
+        &*self.raw
+    }
+
+    /// Returns a mutable pointer to the underlying object, for passing to the `bli_*` functions.
+    pub fn as_mut_ptr(&mut self) -> *mut obj_t {
+        &mut *self.raw
+    }
+}
+
+impl Drop for Obj<'_> {
+    fn drop(&mut self) {
+        // `bli_obj_free` frees the buffer, which is only ours to free if BLIS allocated it.
+        if self.owned {
+            unsafe { crate::bli_obj_free(&mut *self.raw) };
+        }
+    }
+}
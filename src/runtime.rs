@@ -0,0 +1,105 @@
+//! Explicit control over the lifetime of BLIS's global state.
+
+use std::sync::{Mutex, PoisonError};
+
+/// Whether a guard initialized BLIS since it was last finalized.
+static INITIALIZED: Mutex<bool> = Mutex::new(false);
+
+/// Shows that BLIS was initialized.
+///
+/// BLIS initializes itself on first use, but creating a guard controls when its global state,
+/// such as memory and thread pools, is set up. Dropping a guard leaves BLIS initialized, since the
+/// safe wrappers may still be using it on other threads; [`finalize`] tears it down explicitly.
+///
+/// When the crate was built with `BLIS_SYS_DEFAULT_THREADS`, the first guard after initializing
+/// BLIS also sets that number of threads, or one per core with `openmp-default`, unless
+/// `BLIS_NUM_THREADS` or `OMP_NUM_THREADS` is set in the environment. Without a guard, BLIS runs
+/// with the threads the environment asks for.
+#[derive(Debug)]
+pub struct Blis {
+    _private: (),
+}
+
+impl Blis {
+    /// Initializes BLIS, unless it already is.
+    pub fn new() -> Self {
+        let mut initialized = INITIALIZED.lock().unwrap_or_else(PoisonError::into_inner);
+        if !*initialized {
+            unsafe { crate::bli_init() };
+            set_default_threads();
+            *initialized = true;
+        }
+        Self { _private: () }
+    }
+}
+
+impl Default for Blis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Finalizes BLIS, releasing its memory and thread pools. BLIS initializes itself again on its
+/// next use, and the next [`Blis::new`] sets the default number of threads again.
+///
+/// # Safety
+/// No other thread may be calling into BLIS, including through the safe wrappers of this crate,
+/// while BLIS is finalized.
+pub unsafe fn finalize() {
+    let mut initialized = INITIALIZED.lock().unwrap_or_else(PoisonError::into_inner);
+    crate::bli_finalize();
+    *initialized = false;
+}
+
+/// Applies the thread count baked in at build time, or with `openmp-default` one thread per
+/// core, like an OpenMP team. BLIS has already read the environment during initialization,
+/// which takes precedence.
+fn set_default_threads() {
+    let threads = match option_env!("BLIS_SYS_DEFAULT_THREADS") {
+        // The build script checked that it is a positive number.
+        Some(threads) => threads.parse().unwrap(),
+        None if cfg!(feature = "openmp-default") => {
+            std::thread::available_parallelism().map_or(1, |threads| threads.get())
+        }
+        None => return,
+    };
+    let from_env = ["BLIS_NUM_THREADS", "OMP_NUM_THREADS"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some());
+    if !from_env {
+        let threads = crate::dim_t::try_from(threads).unwrap_or(crate::dim_t::MAX);
+        unsafe { crate::bli_thread_set_num_threads(threads) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level1::ddot;
+    use crate::object::Obj;
+    use crate::types::{Conj, DataType};
+
+    #[test]
+    fn guards_come_and_go_while_blis_is_in_use() {
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    let x = [1.0, 2.0, 3.0];
+                    for _ in 0..1000 {
+                        let obj = Obj::new(DataType::Double, 4, 4, 0, 0).unwrap();
+                        assert_eq!(obj.length(), 4);
+                        let dot = ddot(Conj::NoConjugate, Conj::NoConjugate, 3, &x, 1, &x, 1);
+                        assert_eq!(dot.unwrap(), 14.0);
+                    }
+                })
+            })
+            .collect();
+        for _ in 0..1000 {
+            let _outer = Blis::new();
+            let _inner = Blis::default();
+        }
+        for worker in workers {
+            worker.join().unwrap();
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Runtime control of the number of threads used by BLIS.
+
+use crate::dim_t;
+
+fn to_dim(value: usize, name: &str) -> dim_t {
+    assert!(value > 0, "`{}` must be at least 1", name);
+    dim_t::try_from(value).unwrap_or_else(|_| panic!("`{}` is too large: {}", name, value))
+}
+
+/// Sets the number of threads BLIS uses for subsequent operations.
+///
+/// # Panics
+/// Panics if `n` is zero or does not fit in a `dim_t`.
+pub fn set_num_threads(n: usize) {
+    let n = to_dim(n, "n");
+    unsafe { crate::bli_thread_set_num_threads(n) }
+}
+
+/// Returns the number of threads BLIS uses, or `None` if it has not been set, either through
+/// [`set_num_threads`] or the `BLIS_NUM_THREADS` environment variable.
+pub fn get_num_threads() -> Option<usize> {
+    let n = unsafe { crate::bli_thread_get_num_threads() };
+    usize::try_from(n).ok().filter(|&n| n > 0)
+}
+
+/// Sets the number of ways of parallelism for each loop around the microkernel.
+///
+/// # Panics
+/// Panics if any of the arguments is zero or does not fit in a `dim_t`.
+pub fn set_ways(jc: usize, pc: usize, ic: usize, jr: usize, ir: usize) {
+    let jc = to_dim(jc, "jc");
+    let pc = to_dim(pc, "pc");
+    let ic = to_dim(ic, "ic");
+    let jr = to_dim(jr, "jr");
+    let ir = to_dim(ir, "ir");
+    unsafe { crate::bli_thread_set_ways(jc, pc, ic, jr, ir) }
+}
+
+/// How the OpenMP runtime binds BLIS's threads to the places given to [`set_affinity`], as
+/// accepted by `OMP_PROC_BIND`.
+#[cfg(feature = "parallel-openmp")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcBind {
+    /// Binds the threads to places close to the parent thread.
+    Close,
+    /// Spreads the threads evenly across the places.
+    Spread,
+    /// Binds every thread to the same place as the parent thread.
+    Master,
+}
+
+/// Pins BLIS's threads by setting `OMP_PLACES` to `places`, e.g. `"cores"`, `"sockets"` or
+/// `"{0:8},{8:8}"`, and `OMP_PROC_BIND` to `bind`.
+///
+/// BLIS has no affinity setting of its own, and the OpenMP runtime reads these variables once,
+/// so this must be called before the first BLIS call.
+///
+/// # Safety
+/// Like [`std::env::set_var`], must not be called while other threads may read or write the
+/// environment, which includes BLIS's and the OpenMP runtime's threads.
+#[cfg(feature = "parallel-openmp")]
+pub unsafe fn set_affinity(places: &str, bind: ProcBind) {
+    let bind = match bind {
+        ProcBind::Close => "close",
+        ProcBind::Spread => "spread",
+        ProcBind::Master => "master",
+    };
+    std::env::set_var("OMP_PLACES", places);
+    std::env::set_var("OMP_PROC_BIND", bind);
+}
@@ -0,0 +1,256 @@
+//! Type-safe wrappers around the enum-like BLIS parameter types.
+//!
+//! Each wrapper is `#[repr(transparent)]` over the raw type from the bindings and converts to and
+//! from it with `From`/`Into`, so it can be passed directly to the `bli_*` functions.
+
+macro_rules! blis_enum {
+    ($(#[$attr:meta])* $name:ident($raw:ident) { $($variant:ident = $value:ident,)* }) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        pub struct $name(pub crate::$raw);
+
+        #[allow(non_upper_case_globals)]
+        impl $name {
+            $(pub const $variant: Self = Self(crate::$raw::$value);)*
+        }
+
+        impl From<$name> for crate::$raw {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl From<crate::$raw> for $name {
+            fn from(raw: crate::$raw) -> Self {
+                Self(raw)
+            }
+        }
+    };
+}
+
+blis_enum! {
+    /// Whether an operand is transposed and/or conjugated.
+    Trans(trans_t) {
+        NoTranspose = BLIS_NO_TRANSPOSE,
+        Transpose = BLIS_TRANSPOSE,
+        ConjNoTranspose = BLIS_CONJ_NO_TRANSPOSE,
+        ConjTranspose = BLIS_CONJ_TRANSPOSE,
+    }
+}
+
+impl Trans {
+    /// Returns whether the operand is transposed, with or without conjugation.
+    pub fn is_transposed(self) -> bool {
+        self == Self::Transpose || self == Self::ConjTranspose
+    }
+}
+
+blis_enum! {
+    /// Whether an operand is conjugated.
+    Conj(conj_t) {
+        NoConjugate = BLIS_NO_CONJUGATE,
+        Conjugate = BLIS_CONJUGATE,
+    }
+}
+
+blis_enum! {
+    /// The side from which a triangular or symmetric matrix is applied.
+    Side(side_t) {
+        Left = BLIS_LEFT,
+        Right = BLIS_RIGHT,
+    }
+}
+
+blis_enum! {
+    /// The part of a matrix that is stored.
+    Uplo(uplo_t) {
+        Zeros = BLIS_ZEROS,
+        Lower = BLIS_LOWER,
+        Upper = BLIS_UPPER,
+        Dense = BLIS_DENSE,
+    }
+}
+
+blis_enum! {
+    /// Whether the diagonal of a triangular matrix is implicitly one.
+    Diag(diag_t) {
+        NonUnit = BLIS_NONUNIT_DIAG,
+        Unit = BLIS_UNIT_DIAG,
+    }
+}
+
+/// The datatype of the elements of an object, as used by the object API.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DataType {
+    Float,
+    Double,
+    ScComplex,
+    DcComplex,
+}
+
+impl DataType {
+    /// Returns the size in bytes of one element.
+    pub fn size(self) -> usize {
+        match self {
+            DataType::Float => std::mem::size_of::<f32>(),
+            DataType::Double => std::mem::size_of::<f64>(),
+            DataType::ScComplex => std::mem::size_of::<crate::scomplex>(),
+            DataType::DcComplex => std::mem::size_of::<crate::dcomplex>(),
+        }
+    }
+
+    /// Returns whether the elements are complex.
+    pub fn is_complex(self) -> bool {
+        matches!(self, DataType::ScComplex | DataType::DcComplex)
+    }
+}
+
+impl From<DataType> for crate::num_t {
+    fn from(dt: DataType) -> Self {
+        match dt {
+            DataType::Float => crate::num_t::BLIS_FLOAT,
+            DataType::Double => crate::num_t::BLIS_DOUBLE,
+            DataType::ScComplex => crate::num_t::BLIS_SCOMPLEX,
+            DataType::DcComplex => crate::num_t::BLIS_DCOMPLEX,
+        }
+    }
+}
+
+impl TryFrom<crate::num_t> for DataType {
+    type Error = crate::num_t;
+
+    /// Fails with the raw value for `BLIS_INT`, `BLIS_CONSTANT` and unknown values.
+    fn try_from(raw: crate::num_t) -> Result<Self, Self::Error> {
+        match raw {
+            crate::num_t::BLIS_FLOAT => Ok(DataType::Float),
+            crate::num_t::BLIS_DOUBLE => Ok(DataType::Double),
+            crate::num_t::BLIS_SCOMPLEX => Ok(DataType::ScComplex),
+            crate::num_t::BLIS_DCOMPLEX => Ok(DataType::DcComplex),
+            _ => Err(raw),
+        }
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An element type BLIS operates on.
+///
+/// This trait is sealed, since the safe wrappers rely on [`Element::DATA_TYPE`] describing the
+/// layout of `Self`.
+pub trait Element: Copy + sealed::Sealed {
+    /// The datatype BLIS uses for `Self`.
+    const DATA_TYPE: DataType;
+}
+
+macro_rules! element {
+    ($T:ty, $dt:ident) => {
+        impl sealed::Sealed for $T {}
+
+        impl Element for $T {
+            const DATA_TYPE: DataType = DataType::$dt;
+        }
+    };
+}
+
+element!(f32, Float);
+element!(f64, Double);
+element!(crate::scomplex, ScComplex);
+element!(crate::dcomplex, DcComplex);
+
+#[cfg(feature = "num-complex")]
+macro_rules! complex_interop {
+    ($blis:ident, $num:ty, $as_slice:ident, $as_mut_slice:ident) => {
+        // The slice casts below rely on both types being a `#[repr(C)]` pair of floats.
+        const _: () = assert!(
+            std::mem::size_of::<crate::$blis>() == std::mem::size_of::<$num>()
+                && std::mem::align_of::<crate::$blis>() == std::mem::align_of::<$num>(),
+            concat!("`", stringify!($blis), "` and `", stringify!($num), "` have different layouts"),
+        );
+
+        impl From<$num> for crate::$blis {
+            fn from(z: $num) -> Self {
+                Self {
+                    real: z.re,
+                    imag: z.im,
+                }
+            }
+        }
+
+        impl From<crate::$blis> for $num {
+            fn from(z: crate::$blis) -> Self {
+                Self::new(z.real, z.imag)
+            }
+        }
+
+        #[doc = concat!("Reinterprets a slice of `", stringify!($num), "` as a slice of `", stringify!($blis), "`.")]
+        pub fn $as_slice(values: &[$num]) -> &[crate::$blis] {
+            unsafe { std::slice::from_raw_parts(values.as_ptr().cast(), values.len()) }
+        }
+
+        #[doc = concat!("Reinterprets a mutable slice of `", stringify!($num), "` as a mutable slice of `", stringify!($blis), "`.")]
+        pub fn $as_mut_slice(values: &mut [$num]) -> &mut [crate::$blis] {
+            unsafe { std::slice::from_raw_parts_mut(values.as_mut_ptr().cast(), values.len()) }
+        }
+    };
+}
+
+#[cfg(feature = "num-complex")]
+complex_interop!(
+    scomplex,
+    num_complex::Complex32,
+    as_scomplex_slice,
+    as_scomplex_slice_mut
+);
+#[cfg(feature = "num-complex")]
+complex_interop!(
+    dcomplex,
+    num_complex::Complex64,
+    as_dcomplex_slice,
+    as_dcomplex_slice_mut
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::{align_of, offset_of, size_of};
+
+    #[test]
+    fn complex_layout() {
+        assert_eq!(size_of::<crate::scomplex>(), 2 * size_of::<f32>());
+        assert_eq!(align_of::<crate::scomplex>(), align_of::<f32>());
+        assert_eq!(offset_of!(crate::scomplex, imag), size_of::<f32>());
+        assert_eq!(size_of::<crate::dcomplex>(), 2 * size_of::<f64>());
+        assert_eq!(align_of::<crate::dcomplex>(), align_of::<f64>());
+        assert_eq!(offset_of!(crate::dcomplex, imag), size_of::<f64>());
+        assert_eq!(DataType::ScComplex.size(), size_of::<crate::scomplex>());
+        assert_eq!(DataType::DcComplex.size(), size_of::<crate::dcomplex>());
+    }
+
+    #[test]
+    fn wrapper_layout() {
+        assert_eq!(size_of::<Trans>(), size_of::<crate::trans_t>());
+        assert_eq!(size_of::<Conj>(), size_of::<crate::conj_t>());
+        assert_eq!(size_of::<Side>(), size_of::<crate::side_t>());
+        assert_eq!(size_of::<Uplo>(), size_of::<crate::uplo_t>());
+        assert_eq!(size_of::<Diag>(), size_of::<crate::diag_t>());
+    }
+
+    #[cfg(feature = "num-complex")]
+    #[test]
+    fn num_complex_layout() {
+        assert_eq!(
+            size_of::<num_complex::Complex32>(),
+            size_of::<crate::scomplex>()
+        );
+        assert_eq!(
+            size_of::<num_complex::Complex64>(),
+            size_of::<crate::dcomplex>()
+        );
+        let values = [num_complex::Complex64::new(1.0, 2.0)];
+        let z = as_dcomplex_slice(&values)[0];
+        assert_eq!((z.real, z.imag), (1.0, 2.0));
+    }
+}
@@ -0,0 +1,40 @@
+//! Random vectors and matrices from BLIS's own generator, the one its test suite uses.
+//!
+//! Vectors follow the layout of the `level1` module, and matrices have a row stride and a column
+//! stride.
+
+use crate::error::{check_strided, check_vector, to_dim, to_inc, Error};
+use crate::{dcomplex, scomplex};
+
+macro_rules! rand {
+    ($T:ty, $randv:ident, $randm:ident, $raw_randv:ident, $raw_randm:ident) => {
+        #[doc = concat!("Fills the `n` elements of `x` with random values using `", stringify!($raw_randv), "`.")]
+        pub fn $randv(n: usize, x: &mut [$T], incx: usize) -> Result<(), Error> {
+            check_vector("x", x.len(), n, incx)?;
+            let (n, incx) = (to_dim(n)?, to_inc(incx)?);
+            unsafe { crate::$raw_randv(n, x.as_mut_ptr(), incx) };
+            Ok(())
+        }
+
+        #[doc = concat!("Fills the `m` by `n` matrix `x` with random values using `", stringify!($raw_randm), "`.")]
+        pub fn $randm(
+            m: usize,
+            n: usize,
+            x: &mut [$T],
+            rs: usize,
+            cs: usize,
+        ) -> Result<(), Error> {
+            check_strided("x", x.len(), m, n, rs, cs)?;
+            let (m, n, rs, cs) = (to_dim(m)?, to_dim(n)?, to_inc(rs)?, to_inc(cs)?);
+            unsafe {
+                crate::$raw_randm(0, crate::uplo_t::BLIS_DENSE, m, n, x.as_mut_ptr(), rs, cs)
+            };
+            Ok(())
+        }
+    };
+}
+
+rand!(f32, srandv, srandm, bli_srandv, bli_srandm);
+rand!(f64, drandv, drandm, bli_drandv, bli_drandm);
+rand!(scomplex, crandv, crandm, bli_crandv, bli_crandm);
+rand!(dcomplex, zrandv, zrandm, bli_zrandv, bli_zrandm);
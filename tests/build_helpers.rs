@@ -0,0 +1,5 @@
+//! Runs the tests of the build script's helpers, since Cargo does not test build scripts.
+
+#[allow(dead_code)]
+#[path = "../build/helpers.rs"]
+mod helpers;